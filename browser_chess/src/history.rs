@@ -0,0 +1,103 @@
+use dumb_chess::{action::Action, game::ChessGame, notation::san};
+
+// every position the game has been through, so any of them can be looked at
+// again and played on from
+pub struct History {
+    // states[0] is the start and states[n] is after n plies
+    states: Vec<ChessGame>,
+    sans: Vec<String>,
+    // what the bot that made each move had to say about it
+    comments: Vec<Option<String>>,
+    // the ply on the board
+    pub viewing: usize,
+}
+
+impl History {
+    pub fn new(start: ChessGame) -> Self {
+        History { states: vec![start], sans: Vec::new(), comments: Vec::new(), viewing: 0 }
+    }
+
+    pub fn plies(&self) -> usize {
+        self.sans.len()
+    }
+
+    pub fn start(&self) -> &ChessGame {
+        &self.states[0]
+    }
+
+    pub fn shown(&self) -> &ChessGame {
+        &self.states[self.viewing]
+    }
+
+    pub fn latest(&self) -> &ChessGame {
+        self.states.last().unwrap()
+    }
+
+    pub fn at_latest(&self) -> bool {
+        self.viewing == self.plies()
+    }
+
+    // the moves since the start
+    pub fn moves(&self) -> &[Action] {
+        let start = self.start().moves.len();
+        &self.latest().moves[start..]
+    }
+
+    // the moves in san, one per ply
+    pub fn sans(&self) -> &[String] {
+        &self.sans
+    }
+
+    // the comment on each ply, None for moves without one
+    pub fn comments(&self) -> &[Option<String>] {
+        &self.comments
+    }
+
+    // the comment on the move that got to the position being looked at
+    pub fn comment(&self) -> Option<&str> {
+        self.comments.get(self.viewing.checked_sub(1)?)?.as_deref()
+    }
+
+    // plays the move in the position being looked at, anything after it is
+    // forgotten
+    pub fn push(&mut self, act: Action) -> &ChessGame {
+        self.resume();
+        let next = self.latest().step(act);
+        self.sans.push(san(self.latest(), act));
+        self.comments.push(None);
+        self.states.push(next);
+        self.viewing = self.plies();
+        self.latest()
+    }
+
+    // forgets everything after the position being looked at, true if there
+    // was anything to forget
+    pub fn resume(&mut self) -> bool {
+        if self.at_latest() {
+            return false;
+        }
+        self.states.truncate(self.viewing + 1);
+        self.sans.truncate(self.viewing);
+        self.comments.truncate(self.viewing);
+        true
+    }
+
+    // says something about the last move played
+    pub fn set_comment(&mut self, comment: String) {
+        if let Some(last) = self.comments.last_mut() {
+            *last = Some(comment);
+        }
+    }
+
+    pub fn go_to(&mut self, ply: usize) {
+        self.viewing = ply.min(self.plies());
+    }
+
+    pub fn back(&mut self) {
+        self.viewing = self.viewing.saturating_sub(1);
+    }
+
+    pub fn forward(&mut self) {
+        self.go_to(self.viewing + 1);
+    }
+}
@@ -0,0 +1,107 @@
+use dumb_chess::{action::Action, coord::Coord, game::ChessGame, notation::move_squares, strategy::piece_value};
+
+// picking a move with the mouse, either clicking the piece then where it goes
+// or dragging it there
+// nothing in here touches the page, the canvas just passes along which square
+// the pointer is over
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MoveInput {
+    // the piece being moved
+    pub selected: Option<Coord>,
+    // where the pointer is (in canvas pixels) while the piece is held
+    pub dragging: Option<(f64, f64)>,
+    // once a pawn gets to the end, the pieces it could become
+    pub promotions: Vec<Action>,
+}
+
+impl MoveInput {
+    pub fn clear(&mut self) {
+        *self = MoveInput::default();
+    }
+
+    // the moves the selected piece can make
+    pub fn targets(&self, game: &ChessGame) -> Vec<Action> {
+        let Some(from) = self.selected else {
+            return Vec::new();
+        };
+        game.possible_moves(game.turn)
+            .filter(|m| move_squares(*m).0 == from)
+            .collect()
+    }
+
+    // the pointer went down on a square, Some once that finishes a move
+    pub fn press(&mut self, game: &ChessGame, at: Coord, pos: (f64, f64)) -> Option<Action> {
+        if !self.promotions.is_empty() {
+            // anywhere other than one of the choices backs out of the move
+            let choice = self.promotion_squares(game).into_iter().find(|(c, _)| *c == at);
+            self.clear();
+            return choice.map(|(_, act)| act);
+        }
+        if let Some(from) = self.selected {
+            if at != from && self.targets(game).iter().any(|m| move_squares(*m).1 == at) {
+                return self.finish(game, at);
+            }
+        }
+        if game.board.get(at).owner() == Some(game.turn) {
+            self.selected = Some(at);
+            self.dragging = Some(pos);
+        } else {
+            self.clear();
+        }
+        None
+    }
+
+    pub fn drag(&mut self, pos: (f64, f64)) {
+        if self.dragging.is_some() {
+            self.dragging = Some(pos);
+        }
+    }
+
+    // letting go somewhere else than where the piece started moves it there,
+    // letting go where it started leaves it selected for a click on the target
+    pub fn release(&mut self, game: &ChessGame, at: Coord) -> Option<Action> {
+        self.dragging.take()?;
+        if self.selected == Some(at) {
+            return None;
+        }
+        self.finish(game, at)
+    }
+
+    fn finish(&mut self, game: &ChessGame, to: Coord) -> Option<Action> {
+        let mut moves: Vec<Action> = self.targets(game).into_iter()
+            .filter(|m| move_squares(*m).1 == to)
+            .collect();
+        // a queen first for promotions
+        moves.sort_by(|a, b| promoted_value(*b).total_cmp(&promoted_value(*a)));
+        match moves.len() {
+            0 => None,
+            1 => {
+                self.clear();
+                Some(moves[0])
+            }
+            // the same squares with different pieces at the end, so ask which one
+            _ => {
+                self.dragging = None;
+                self.promotions = moves;
+                None
+            }
+        }
+    }
+
+    // the promotion choices go on the square the pawn is going to and the
+    // ones back down the board from it
+    pub fn promotion_squares(&self, game: &ChessGame) -> Vec<(Coord, Action)> {
+        let back = game.turn.toggle().pawn_dir();
+        self.promotions.iter()
+            .enumerate()
+            .map(|(i, m)| {
+                let to = move_squares(*m).1;
+                (Coord::new(to.row + back.row * i as i32, to.col), *m)
+            })
+            .collect()
+    }
+}
+
+fn promoted_value(action: Action) -> f32 {
+    move_squares(action).2.map_or(0.0, piece_value)
+}
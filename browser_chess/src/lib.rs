@@ -1,12 +1,24 @@
 mod utils;
+pub mod input;
+pub mod history;
+pub mod playback;
+pub mod session;
+pub mod sprites;
+pub mod thinker;
+pub mod tournament;
 
 
 use wasm_bindgen::prelude::*;
 
 use std::{f64};
-use enum_iterator::all;
 
-use dumb_chess::{strategy::{*}, game::ChessGame, player::Player, final_state::FinalState};
+use dumb_chess::{strategy::{*}, game::ChessGame, definition, registry::{Registry, Entry}, action::Action, coord::Coord, notation::move_squares, piece::Piece};
+use input::MoveInput;
+use history::History;
+use playback::Mode;
+use session::{read_game, seat_for, Answer, Session, HUMAN};
+use sprites::Sprites;
+use thinker::Reply;
 
 #[wasm_bindgen]
 extern "C" {
@@ -29,116 +41,341 @@ pub fn greet(s: &str) {
  * - reset the board
  */
 
-use Player::*;
-use FinalState::*;
-
-#[wasm_bindgen]
-#[derive(Debug, Clone)]
-pub struct GameState {
-    turn: Player,
+// the parts of the page the game draws on and reads from
+struct Page {
     canvas: web_sys::HtmlCanvasElement,
     status: web_sys::HtmlElement,
     white_select: web_sys::HtmlSelectElement,
     black_select: web_sys::HtmlSelectElement,
-    white: Strategy,
-    black: Strategy,
-    game: ChessGame,
+    descriptions: web_sys::HtmlElement,
+    move_list: web_sys::HtmlElement,
+    // the evaluation bar and the comment on the last move
+    analysis: web_sys::HtmlElement,
 }
 
+// the page side of the game, the game itself is in the session
 #[wasm_bindgen]
-#[derive(Debug, Clone, Copy)]
-pub enum State {
-    Playing,
-    Draw,
-    WinWhite,
-    WinBlack,
+pub struct GameState {
+    page: Page,
+    registry: Registry,
+    session: Session,
+    input: MoveInput,
+    // black at the bottom
+    flipped: bool,
+    sprites: Sprites,
 }
 
-impl From<Option<FinalState>> for State {
-    fn from(value: Option<FinalState>) -> Self {
-        match value {
-            None => State::Playing,
-            Some(Draw) => State::Draw,
-            Some(Win(White)) => State::WinWhite,
-            Some(Win(Black)) => State::WinBlack,
-        }       
+impl GameState {
+    fn new(page: Page, black: Strategy, white: Strategy) -> Self {
+        GameState {
+            page,
+            registry: Registry::new(),
+            session: Session::new(seat_for(white.name()), seat_for(black.name())),
+            input: MoveInput::default(),
+            flipped: false,
+            sprites: Sprites::load().expect("should be able to make images"),
+        }
     }
 }
 
 #[wasm_bindgen]
 impl GameState {
-    pub fn new(canvas: web_sys::HtmlCanvasElement,
-               status: web_sys::HtmlElement,
-               white_select: web_sys::HtmlSelectElement,
-               black_select: web_sys::HtmlSelectElement,
-               black: Strategy,
-               white: Strategy)
-        -> Self {
-        GameState { turn: Player::White, canvas, status, white_select, black_select, white, black , game: ChessGame::new() }
-    }
-    
     #[wasm_bindgen]
     pub fn reset(&mut self) {
-        let white = get_selection(&self.white_select);
-        let black = get_selection(&self.black_select);
-        self.white = white;
-        self.black = black;
-        self.turn = White;
-        self.game = ChessGame::new();
-    }
-
-    pub fn step(&mut self) -> State {
-        if let Some(act) = 
-            match self.turn {
-                White => {
-                    self.white.run(&self.game)
-                }
-                Black => {
-                    self.black.run(&self.game)
-                }
+        let white = seat_for(&self.page.white_select.value());
+        let black = seat_for(&self.page.black_select.value());
+        self.session.reset(white, black);
+        self.show_view();
+    }
+
+    // the position on the board, which might be from earlier in the game
+    fn game(&self) -> &ChessGame {
+        self.session.game()
+    }
+
+    // called every frame, a Request (json) for the worker when it's time for
+    // a bot to move
+    #[wasm_bindgen]
+    pub fn tick(&mut self) -> Option<String> {
+        let mode = self.session.playback.mode;
+        let request = self.session.tick(js_sys::Date::now());
+        if request.is_some() || self.session.playback.mode != mode {
+            self.show_view();
+        }
+        serde_json::to_string(&request?).ok()
+    }
+
+    // the worker's Reply (json)
+    #[wasm_bindgen]
+    pub fn answer(&mut self, reply: &str) {
+        let Ok(reply) = serde_json::from_str::<Reply>(reply) else {
+            return;
+        };
+        if self.session.answer(reply, js_sys::Date::now()) != Answer::Ignored {
+            self.show_view();
+        }
+    }
+
+    // the controls, the status keeps up with these
+    #[wasm_bindgen]
+    pub fn play(&mut self) {
+        self.session.playback.play();
+        self.show_status();
+    }
+
+    #[wasm_bindgen]
+    pub fn pause(&mut self) {
+        self.session.playback.pause();
+        self.show_status();
+    }
+
+    #[wasm_bindgen]
+    pub fn toggle_play(&mut self) {
+        self.session.playback.toggle();
+        self.show_status();
+    }
+
+    // one bot move, then paused
+    #[wasm_bindgen]
+    pub fn step(&mut self) {
+        self.session.playback.step();
+        self.show_status();
+    }
+
+    #[wasm_bindgen]
+    pub fn playing(&self) -> bool {
+        self.session.playback.mode != Mode::Paused
+    }
+
+    // milliseconds between bot moves
+    #[wasm_bindgen]
+    pub fn set_delay(&mut self, delay: f64) {
+        self.session.playback.delay = delay.max(0.0);
+    }
+
+    // whether a move has been asked for and not come back yet
+    #[wasm_bindgen]
+    pub fn thinking(&self) -> bool {
+        self.session.playback.waiting.is_some()
+    }
+
+    // for when the worker has been stopped, play asks again
+    #[wasm_bindgen]
+    pub fn cancel(&mut self) {
+        self.session.playback.cancel();
+        self.show_status();
+    }
+
+    #[wasm_bindgen]
+    pub fn flip(&mut self) {
+        self.flipped = !self.flipped;
+        self.input.clear();
+        self.render();
+    }
+
+    // a fen starts a new game from there, anything else is read as pgn and
+    // played through, and the players named in it are picked if they're here
+    #[wasm_bindgen]
+    pub fn load(&mut self, text: &str) -> Result<(), JsValue> {
+        let loaded = read_game(text).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        for (name, select) in [(&loaded.white, &self.page.white_select), (&loaded.black, &self.page.black_select)] {
+            if let Some(name) = name.as_deref().filter(|name| *name == HUMAN || self.registry.contains(name)) {
+                select.set_value(name);
             }
-        {
-            self.game = self.game.step(act);
-            self.turn = self.turn.toggle();
-            self.game.check_state().into()
-        } else {
-            Some(Draw).into()
         }
+        let white = seat_for(&self.page.white_select.value());
+        let black = seat_for(&self.page.black_select.value());
+        self.session.start_from(loaded.start, loaded.moves, white, black);
+        self.show_view();
+        Ok(())
     }
 
-    pub fn render(&self) {
-        render_board(&self.canvas, &self.game)
+    // the position on the board
+    #[wasm_bindgen]
+    pub fn fen(&self) -> String {
+        self.session.fen()
+    }
+
+    // the whole game, not just up to the position on the board
+    #[wasm_bindgen]
+    pub fn pgn(&self) -> String {
+        self.session.pgn()
+    }
+
+    #[wasm_bindgen]
+    pub fn back(&mut self) {
+        self.session.back();
+        self.show_view();
+    }
+
+    #[wasm_bindgen]
+    pub fn forward(&mut self) {
+        self.session.forward();
+        self.show_view();
+    }
+
+    #[wasm_bindgen]
+    pub fn go_to(&mut self, ply: usize) {
+        self.session.go_to(ply);
+        self.show_view();
+    }
+
+    fn show_view(&mut self) {
+        self.input.clear();
+        self.render();
+        self.show_moves();
+        self.show_status();
+        let _ = self.show_analysis();
+    }
+
+    // the pointer handlers take positions in css pixels relative to the canvas
+    #[wasm_bindgen]
+    pub fn mouse_down(&mut self, x: f64, y: f64) {
+        if !self.session.human_turn() {
+            return;
+        }
+        let pos = self.canvas_pos(x, y);
+        if let Some(at) = square_at(pos, self.square_size(), self.flipped) {
+            let act = self.input.press(self.session.game(), at, pos);
+            self.human_move(act);
+        }
+        self.render();
+    }
+
+    #[wasm_bindgen]
+    pub fn mouse_move(&mut self, x: f64, y: f64) {
+        if self.input.dragging.is_some() {
+            let pos = self.canvas_pos(x, y);
+            self.input.drag(pos);
+            self.render();
+        }
     }
 
     #[wasm_bindgen]
-    pub fn loop_step(&mut self) -> bool {
-        let state = self.step();
+    pub fn mouse_up(&mut self, x: f64, y: f64) {
+        if !self.session.human_turn() {
+            return;
+        }
+        let pos = self.canvas_pos(x, y);
+        let act = match square_at(pos, self.square_size(), self.flipped) {
+            Some(at) => self.input.release(self.session.game(), at),
+            // dropped off the board, put it back
+            None => {
+                self.input.dragging = None;
+                None
+            }
+        };
+        self.human_move(act);
         self.render();
-        match state {
-            State::Playing => {
-                if self.turn == White {
-                    self.status.set_text_content(Some("White's turn"));
-                } else { 
-                    self.status.set_text_content(Some("Black's turn"));
-                }
-                false
+    }
+
+    fn human_move(&mut self, act: Option<Action>) {
+        if let Some(act) = act {
+            self.session.human_move(act);
+            self.show_view();
+        }
+    }
+
+    fn square_size(&self) -> f64 {
+        self.page.canvas.width() as f64 / 8.0
+    }
+
+    // the canvas can be drawn bigger or smaller than it really is
+    fn canvas_pos(&self, x: f64, y: f64) -> (f64, f64) {
+        let scale = self.page.canvas.width() as f64 / self.page.canvas.client_width().max(1) as f64;
+        (x * scale, y * scale)
+    }
+
+    // add the strategies from a definition file (toml or json) to the selections
+    #[wasm_bindgen]
+    pub fn load_definitions(&mut self, text: &str) -> Result<(), JsValue> {
+        let defs = definition::parse(text).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        // replacing a strategy keeps the option that is already there
+        let mut added: Vec<String> = Vec::new();
+        for def in &defs {
+            if !self.registry.contains(&def.name) && !added.contains(&def.name) {
+                added.push(def.name.clone());
             }
-            State::Draw => {
-                self.status.set_text_content(Some("Game over: Draw"));
-                true
-            },
-            State::WinWhite => {
-                self.status.set_text_content(Some("White wins"));
-                true
-            },
-            State::WinBlack => {
-                self.status.set_text_content(Some("Black wins"));
-                true
-            },
         }
+        self.registry.register_definitions(defs);
+        for name in added {
+            let entry = self.registry.get(&name).unwrap();
+            add_option(&self.page.white_select, entry)?;
+            add_option(&self.page.black_select, entry)?;
+            add_description(&self.page.descriptions, entry)?;
+        }
+        Ok(())
+    }
+
+    pub fn render(&self) {
+        render_board(&self.page.canvas, self.game(), &self.input, &self.sprites, self.flipped)
+    }
+
+    // white's share of the bar, the score, and why the last move was played
+    fn show_analysis(&self) -> Result<(), JsValue> {
+        let (share, text) = self.session.evaluation();
+        let analysis = &self.page.analysis;
+        if let Some(white) = analysis.query_selector(".eval-white")? {
+            white.set_attribute("style", &format!("width: {:.1}%", share * 100.0))?;
+        }
+        if let Some(score) = analysis.query_selector(".eval-text")? {
+            score.set_text_content(Some(&text));
+        }
+        if let Some(comment) = analysis.query_selector(".comment")? {
+            comment.set_text_content(self.session.history.comment());
+        }
+        Ok(())
+    }
+
+    fn show_status(&self) {
+        self.page.status.set_text_content(Some(&self.session.status()));
+    }
+
+    // the moves so far in san, two plies to a line, clicking one goes to the
+    // position after it
+    fn show_moves(&self) {
+        let _ = fill_move_list(&self.page.move_list, &self.session.history);
     }
 }
 
+fn fill_move_list(move_list: &web_sys::HtmlElement, history: &History) -> Result<(), JsValue> {
+    let document = window().document().expect("Windows should have a document");
+    move_list.set_text_content(None);
+    let start = document.create_element("span")?;
+    start.set_text_content(Some("Start"));
+    start.set_attribute("data-ply", "0")?;
+    start.set_class_name(if history.viewing == 0 { "ply current" } else { "ply" });
+    move_list.append_child(&start)?;
+
+    // a game from a set up position can start with black to move
+    let first = history.start().turn_number as usize;
+    let mut line = document.create_element("div")?;
+    for (i, san) in history.sans().iter().enumerate() {
+        let ply_number = first + i;
+        if i == 0 || ply_number.is_multiple_of(2) {
+            line = document.create_element("div")?;
+            let number = document.create_element("span")?;
+            let dots = if ply_number.is_multiple_of(2) { "." } else { "..." };
+            number.set_text_content(Some(&format!("{}{}", ply_number / 2 + 1, dots)));
+            number.set_class_name("number");
+            line.append_child(&number)?;
+            move_list.append_child(&line)?;
+        }
+        let ply = document.create_element("span")?;
+        ply.set_text_content(Some(san));
+        ply.set_attribute("data-ply", &(i + 1).to_string())?;
+        if let Some(Some(comment)) = history.comments().get(i) {
+            ply.set_attribute("title", comment)?;
+        }
+        ply.set_class_name(if history.viewing == i + 1 { "ply current" } else { "ply" });
+        line.append_child(&ply)?;
+    }
+    if history.at_latest() {
+        move_list.set_scroll_top(move_list.scroll_height());
+    }
+    Ok(())
+}
+
 fn window() -> web_sys::Window {
     web_sys::window().expect("no global `window` exists")
 }
@@ -148,86 +385,236 @@ pub fn setup(canvas: web_sys::HtmlCanvasElement,
            status: web_sys::HtmlElement,
            white_select: web_sys::HtmlSelectElement,
            black_select: web_sys::HtmlSelectElement,
-           descriptions: web_sys::HtmlElement)
+           descriptions: web_sys::HtmlElement,
+           move_list: web_sys::HtmlElement,
+           analysis: web_sys::HtmlElement)
     -> GameState {
     
-    let _ = setup_descriptions(descriptions);
-    let _ = setup_select(&white_select);
-    let _ = setup_select(&black_select);
-    
-   GameState::new(canvas,
-                              status,
-                              white_select,
-                              black_select,
-                              Strategy::Random,
-                              Strategy::Random) 
+    utils::set_panic_hook();
+    let page = Page { canvas, status, white_select, black_select, descriptions, move_list, analysis };
+    let game = GameState::new(page, Strategy::Random, Strategy::Random);
+
+    let _ = setup_descriptions(&game.page.descriptions, &game.registry);
+    let _ = setup_select(&game.page.white_select, &game.registry);
+    let _ = setup_select(&game.page.black_select, &game.registry);
+    let _ = setup_analysis(&game.page.analysis);
+    game.show_status();
+    let _ = game.show_analysis();
+    game
 }
 
+// a bar with white's share filled in, the score next to it, and the comment
+// underneath
+fn setup_analysis(analysis: &web_sys::HtmlElement) -> Result<(), JsValue> {
+    let document = window().document().expect("Windows should have a document");
+    let bar = document.create_element("div")?;
+    bar.set_class_name("eval-bar");
+    let white = document.create_element("div")?;
+    white.set_class_name("eval-white");
+    bar.append_child(&white)?;
+    let score = document.create_element("span")?;
+    score.set_class_name("eval-text");
+    let comment = document.create_element("p")?;
+    comment.set_class_name("comment");
+    analysis.append_child(&bar)?;
+    analysis.append_child(&score)?;
+    analysis.append_child(&comment)?;
+    Ok(())
+}
 
-fn setup_select(select: &web_sys::HtmlSelectElement) -> Result<(), JsValue> {
-    let window = web_sys::window().expect("No global window");
-    let document = window.document().expect("Windows should have a document");
 
-    for strategy in all::<Strategy>() {
-        let option = document.create_element("option")?;
-        option.set_text_content(Some(strategy.name()));
-        let _ = select.append_child(&option);
+fn setup_select(select: &web_sys::HtmlSelectElement, registry: &Registry) -> Result<(), JsValue> {
+    add_named_option(select, HUMAN)?;
+    for entry in registry.entries() {
+        add_option(select, entry)?;
     }
+    // bots playing each other is still what happens at first
+    select.set_selected_index(1);
     Ok(())
 }
 
-fn get_selection(select: &web_sys::HtmlSelectElement) -> Strategy {
-    let name = select.value();
-    *strategy_map().get(&name).unwrap()
+fn add_option(select: &web_sys::HtmlSelectElement, entry: &Entry) -> Result<(), JsValue> {
+    add_named_option(select, entry.name())
 }
 
-fn setup_descriptions(descriptions: web_sys::HtmlElement) -> Result<(), JsValue> {
+fn add_named_option(select: &web_sys::HtmlSelectElement, name: &str) -> Result<(), JsValue> {
+    let document = window().document().expect("Windows should have a document");
+    let option = document.create_element("option")?;
+    option.set_text_content(Some(name));
+    let _ = select.append_child(&option);
+    Ok(())
+}
+
+fn setup_descriptions(descriptions: &web_sys::HtmlElement, registry: &Registry) -> Result<(), JsValue> {
     let document = window().document().expect("Windows should have a document");
 
     let table = document.create_element("table")?;
-    for strategy in all::<Strategy>() {
-        let row = document.create_element("tr")?;
-        let name_cell = document.create_element("td")?;
-        let desc_cell = document.create_element("td")?;
-        name_cell.set_text_content(Some(strategy.name()));
-        desc_cell.set_text_content(Some(strategy.description()));
-        let _ = row.append_child(&name_cell);
-        let _ = row.append_child(&desc_cell);
-        let _ = table.append_child(&row);
-    }
     let _ = descriptions.append_child(&table);
+    for entry in registry.entries() {
+        add_description(descriptions, entry)?;
+    }
     Ok(())
 }
 
-fn render_board(canvas: &web_sys::HtmlCanvasElement, game: &ChessGame) {
-    let grid_size = 50;
-    canvas.set_width(8*grid_size);
-    canvas.set_height(8*grid_size);
+fn add_description(descriptions: &web_sys::HtmlElement, entry: &Entry) -> Result<(), JsValue> {
+    let document = window().document().expect("Windows should have a document");
+    let table = descriptions.query_selector("table")?.expect("descriptions should have a table");
+
+    let row = document.create_element("tr")?;
+    let name_cell = document.create_element("td")?;
+    let desc_cell = document.create_element("td")?;
+    name_cell.set_text_content(Some(entry.name()));
+    desc_cell.set_text_content(Some(entry.description()));
+    let _ = row.append_child(&name_cell);
+    let _ = row.append_child(&desc_cell);
+    let _ = table.append_child(&row);
+    Ok(())
+}
+
+const LIGHT: &str = "#FFCE9E";
+const DARK: &str = "#D18B47";
+
+// the square under a point on the canvas, size being how big a square is
+fn square_at((x, y): (f64, f64), size: f64, flipped: bool) -> Option<Coord> {
+    let (row, col) = ((y / size).floor(), (x / size).floor());
+    if (0.0..8.0).contains(&row) && (0.0..8.0).contains(&col) {
+        Some(on_screen(Coord::new(row as i32, col as i32), flipped))
+    } else {
+        None
+    }
+}
+
+// where a square is drawn, turning the board around goes both ways so this
+// also takes where it's drawn back to the square
+fn on_screen(at: Coord, flipped: bool) -> Coord {
+    if flipped {
+        Coord::new(7 - at.row, 7 - at.col)
+    } else {
+        at
+    }
+}
+
+// the canvas gets as many pixels as the screen has where it is drawn, so it
+// stays sharp however big the page makes it and on high dpi screens
+fn fit_canvas(canvas: &web_sys::HtmlCanvasElement) -> f64 {
+    let shown = match canvas.client_width() {
+        0 => 400.0,
+        w => w as f64,
+    };
+    let pixels = (shown * window().device_pixel_ratio()).round() as u32;
+    if canvas.width() != pixels || canvas.height() != pixels {
+        canvas.set_width(pixels);
+        canvas.set_height(pixels);
+    }
+    pixels as f64 / 8.0
+}
+
+fn render_board(canvas: &web_sys::HtmlCanvasElement, game: &ChessGame, input: &MoveInput, sprites: &Sprites, flipped: bool) {
+    let size = fit_canvas(canvas);
     let context = canvas
         .get_context("2d")
         .unwrap()
         .unwrap()
         .dyn_into::<web_sys::CanvasRenderingContext2d>()
         .unwrap();
-    for row in 0..8 {
-        for col in 0..8 {
-            let color = if (row + col) % 2 == 0 {
-                JsValue::from_str("#FFCE9E")
-            } else {
-                JsValue::from_str("#D18B47")
-            };
-            context.set_fill_style(&color);
-            context.fill_rect((row*grid_size) as f64, (col*grid_size) as f64, grid_size as f64, grid_size as f64);
+    // the top left corner of where a square is drawn
+    let corner = |at: Coord| {
+        let at = on_screen(at, flipped);
+        (at.col as f64 * size, at.row as f64 * size)
+    };
+    let square = |at: Coord, color: &str| {
+        let (x, y) = corner(at);
+        context.set_fill_style(&JsValue::from_str(color));
+        context.fill_rect(x, y, size, size);
+    };
+    let centre = |at: Coord| {
+        let (x, y) = corner(at);
+        (x + size/2.0, y + size/2.0)
+    };
+    let draw = |piece, x: f64, y: f64| {
+        if let Some(image) = sprites.get(piece) {
+            let _ = context.draw_image_with_html_image_element_and_dw_and_dh(image, x, y, size, size);
+        }
+    };
+
+    for at in Coord::all_coords() {
+        square(at, if (at.row + at.col) % 2 == 0 { LIGHT } else { DARK });
+    }
+
+    if let Some(last) = game.moves.last() {
+        let (from, to, _) = move_squares(*last);
+        square(from, "rgba(155, 199, 0, 0.41)");
+        square(to, "rgba(155, 199, 0, 0.41)");
+    }
+    if let Some(from) = input.selected {
+        square(from, "rgba(20, 85, 30, 0.5)");
+    }
+    let king = game.board.pieces_for(game.turn).find(|p| matches!(p.piece, Piece::King(_, _)));
+    if let Some(king) = king.filter(|_| game.in_check(game.turn)) {
+        let (x, y) = centre(king.coord);
+        if let Ok(glow) = context.create_radial_gradient(x, y, 0.0, x, y, size * 0.7) {
+            let _ = glow.add_color_stop(0.0, "rgba(255, 0, 0, 1)");
+            let _ = glow.add_color_stop(0.25, "rgba(231, 0, 0, 1)");
+            let _ = glow.add_color_stop(0.9, "rgba(169, 0, 0, 0)");
+            context.set_fill_style(&glow);
+            let (x, y) = corner(king.coord);
+            context.fill_rect(x, y, size, size);
+        }
+    }
+
+    // files along the bottom and ranks down the left, in the other square color
+    // (i counts down and across the screen, which is the same either way up)
+    context.set_font(&format!("bold {}px sans-serif", (size * 0.2).round()));
+    for i in 0..8 {
+        let rank = on_screen(Coord::new(i, 0), flipped);
+        context.set_fill_style(&JsValue::from_str(if i % 2 == 0 { DARK } else { LIGHT }));
+        context.set_text_align("left");
+        context.set_text_baseline("top");
+        let _ = context.fill_text(&(8 - rank.row).to_string(), size*0.05, i as f64 * size + size*0.05);
+        let file = on_screen(Coord::new(7, i), flipped);
+        context.set_fill_style(&JsValue::from_str(if i % 2 == 0 { LIGHT } else { DARK }));
+        context.set_text_align("right");
+        context.set_text_baseline("bottom");
+        let name = ((b'a' + file.col as u8) as char).to_string();
+        let _ = context.fill_text(&name, (i + 1) as f64 * size - size*0.05, 8.0 * size - size*0.03);
+    }
+
+    for p in game.board.pieces() {
+        // the piece being dragged is drawn under the pointer instead
+        if input.dragging.is_some() && input.selected == Some(p.coord) {
+            continue;
+        }
+        let (x, y) = corner(p.coord);
+        draw(p.piece, x, y);
+    }
+
+    // a dot on everywhere the selected piece can go, a ring around anything it can take
+    context.set_fill_style(&JsValue::from_str("rgba(20, 85, 30, 0.5)"));
+    context.set_stroke_style(&JsValue::from_str("rgba(20, 85, 30, 0.5)"));
+    context.set_line_width(size * 0.08);
+    for act in input.targets(game) {
+        let (_, to, _) = move_squares(act);
+        let (x, y) = centre(to);
+        context.begin_path();
+        if game.board.get(to) == Piece::Empty {
+            let _ = context.arc(x, y, size/6.0, 0.0, f64::consts::TAU);
             context.fill();
+        } else {
+            let _ = context.arc(x, y, size*0.45, 0.0, f64::consts::TAU);
+            context.stroke();
         }
     }
 
-    context.set_font("50px Arial");
-    context.set_fill_style(&JsValue::from_str("black"));
-    for row in 0..8 {
-        for col in 0..8 {
-            let piece = game.board.get((row as i32, col as i32).into());
-            let _ = context.fill_text(&format!("{}", piece), ((col)*grid_size) as f64, ((row+1)*grid_size-5) as f64);
+    if let (Some(from), Some((x, y))) = (input.selected, input.dragging) {
+        draw(game.board.get(from), x - size/2.0, y - size/2.0);
+    }
+
+    // what the pawn can turn into, over the top of everything
+    for (at, act) in input.promotion_squares(game) {
+        if let Some(piece) = move_squares(act).2 {
+            square(at, "#EEEEEE");
+            let (x, y) = corner(at);
+            draw(piece, x, y);
         }
     }
 }
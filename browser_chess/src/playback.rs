@@ -0,0 +1,87 @@
+// whether the bots are playing, and when the next move is due
+// nothing in here touches the page, times are in milliseconds from whatever
+// clock the caller uses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Paused,
+    Playing,
+    // one more bot move and then paused
+    Stepping,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Playback {
+    pub mode: Mode,
+    // how long to wait between bot moves while playing
+    pub delay: f64,
+    // the request the worker is working on
+    pub waiting: Option<u32>,
+    last_move: f64,
+    requests: u32,
+}
+
+impl Playback {
+    pub fn new(delay: f64) -> Self {
+        Playback { mode: Mode::Playing, delay, waiting: None, last_move: f64::NEG_INFINITY, requests: 0 }
+    }
+
+    pub fn play(&mut self) {
+        self.mode = Mode::Playing;
+    }
+
+    pub fn pause(&mut self) {
+        self.mode = Mode::Paused;
+    }
+
+    pub fn toggle(&mut self) {
+        self.mode = match self.mode {
+            Mode::Paused => Mode::Playing,
+            Mode::Playing | Mode::Stepping => Mode::Paused,
+        };
+    }
+
+    pub fn step(&mut self) {
+        self.mode = Mode::Stepping;
+    }
+
+    // time to ask for the next move, a step doesn't wait for the delay
+    pub fn due(&self, now: f64) -> bool {
+        self.waiting.is_none() && match self.mode {
+            Mode::Paused => false,
+            Mode::Playing => now - self.last_move >= self.delay,
+            Mode::Stepping => true,
+        }
+    }
+
+    // a request is going out, the id goes with it so the answer can be
+    // matched up
+    pub fn ask(&mut self) -> u32 {
+        self.requests += 1;
+        self.waiting = Some(self.requests);
+        self.requests
+    }
+
+    // true if this is the answer being waited for
+    pub fn answered(&mut self, id: u32, now: f64) -> bool {
+        if self.waiting != Some(id) {
+            return false;
+        }
+        self.waiting = None;
+        self.last_move = now;
+        if self.mode == Mode::Stepping {
+            self.mode = Mode::Paused;
+        }
+        true
+    }
+
+    // the game changed under the request, so its answer isn't wanted anymore
+    pub fn forget(&mut self) {
+        self.waiting = None;
+    }
+
+    // the worker is being thrown away along with whatever it was doing
+    pub fn cancel(&mut self) {
+        self.forget();
+        self.pause();
+    }
+}
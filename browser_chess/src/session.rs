@@ -0,0 +1,323 @@
+use wasm_bindgen::prelude::*;
+
+use dumb_chess::{
+    action::Action,
+    commentary,
+    final_state::FinalState::{self, *},
+    game::ChessGame,
+    notation::{from_fen, from_pgn, parse_uci, to_fen, uci, write_pgn, ParsePgnError},
+    player::Player::{self, *},
+};
+
+use crate::history::History;
+use crate::playback::{Mode, Playback};
+use crate::thinker::{Reply, Request};
+
+// everything about the game being played on the page that isn't the page
+// itself: who is playing, the moves so far, when the bots move and what the
+// status should say, so it all works (and can be tested) without a browser
+// times are milliseconds from whatever clock the page has
+
+// the select entry for a person playing with the mouse
+pub const HUMAN: &str = "Human";
+
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum State {
+    Playing,
+    Draw,
+    WinWhite,
+    WinBlack,
+}
+
+impl From<Option<FinalState>> for State {
+    fn from(value: Option<FinalState>) -> Self {
+        match value {
+            None => State::Playing,
+            Some(Draw) => State::Draw,
+            Some(Win(White)) => State::WinWhite,
+            Some(Win(Black)) => State::WinBlack,
+        }
+    }
+}
+
+// what a name picked for a player means, None is a person playing
+pub fn seat_for(name: &str) -> Option<String> {
+    if name == HUMAN {
+        return None;
+    }
+    Some(name.to_string())
+}
+
+// what came out of the worker's reply
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Answer {
+    // not the request being waited on
+    Ignored,
+    // the game moved on while the bot was thinking
+    Stale,
+    Moved(Action),
+    // the bot didn't come up with anything, which the cli counts as a draw
+    GaveUp,
+    // the worker fell over, the game waits for play to try again
+    Failed,
+}
+
+// a game read from a fen or a pgn, with the players the pgn named
+#[derive(Debug, Clone)]
+pub struct Loaded {
+    pub start: ChessGame,
+    pub moves: Vec<Action>,
+    pub white: Option<String>,
+    pub black: Option<String>,
+}
+
+// a fen starts from that position, anything else is read as pgn
+pub fn read_game(text: &str) -> Result<Loaded, ParsePgnError> {
+    if let Ok(start) = from_fen(text) {
+        return Ok(Loaded { start, moves: Vec::new(), white: None, black: None });
+    }
+    let pgn = from_pgn(text)?;
+    Ok(Loaded {
+        white: pgn.tag("White").map(|name| name.to_string()),
+        black: pgn.tag("Black").map(|name| name.to_string()),
+        start: pgn.start,
+        moves: pgn.moves,
+    })
+}
+
+pub struct Session {
+    // the bots themselves are in the worker (see thinker.rs), None is a
+    // person playing
+    pub white: Option<String>,
+    pub black: Option<String>,
+    // goes up every time the worker's bots need to start over
+    pub generation: u32,
+    pub history: History,
+    pub playback: Playback,
+    // who didn't come up with a move
+    pub gave_up: Option<Player>,
+    // what went wrong the last time the worker was asked
+    pub error: Option<String>,
+}
+
+impl Session {
+    pub fn new(white: Option<String>, black: Option<String>) -> Self {
+        Session {
+            white,
+            black,
+            generation: 0,
+            history: History::new(ChessGame::new()),
+            playback: Playback::new(100.0),
+            gave_up: None,
+            error: None,
+        }
+    }
+
+    // the position on the board, which might be from earlier in the game
+    pub fn game(&self) -> &ChessGame {
+        self.history.shown()
+    }
+
+    pub fn seat(&self, player: Player) -> Option<&String> {
+        match player {
+            White => self.white.as_ref(),
+            Black => self.black.as_ref(),
+        }
+    }
+
+    pub fn name(&self, player: Player) -> String {
+        self.seat(player).cloned().unwrap_or(HUMAN.to_string())
+    }
+
+    pub fn over(&self) -> bool {
+        self.gave_up.is_some() || self.game().check_state().is_some()
+    }
+
+    // a person can move the pieces
+    pub fn human_turn(&self) -> bool {
+        self.seat(self.game().turn).is_none() && !self.over()
+    }
+
+    // a new game between these two, playing straight away
+    pub fn reset(&mut self, white: Option<String>, black: Option<String>) {
+        self.start_from(ChessGame::new(), Vec::new(), white, black);
+        self.playback.play();
+    }
+
+    // the moves played through from the start, paused so the game can be
+    // looked over first
+    pub fn start_from(&mut self, start: ChessGame, moves: Vec<Action>, white: Option<String>, black: Option<String>) {
+        self.white = white;
+        self.black = black;
+        self.generation += 1;
+        self.playback.forget();
+        self.playback.pause();
+        self.gave_up = None;
+        self.error = None;
+        self.history = History::new(start);
+        for act in moves {
+            self.history.push(act);
+        }
+    }
+
+    // playing from an earlier position, whatever the bots remember about the
+    // moves that got undone doesn't count anymore
+    fn resume(&mut self) {
+        if self.history.resume() {
+            self.generation += 1;
+            self.playback.forget();
+            self.gave_up = None;
+        }
+    }
+
+    // a Request for the worker when it's time for a bot to move, this carries
+    // on from the position on the board
+    pub fn tick(&mut self, now: f64) -> Option<Request> {
+        if !self.playback.due(now) {
+            return None;
+        }
+        self.resume();
+        let over = self.over();
+        // people move with the mouse, so just keep waiting
+        if over || self.seat(self.game().turn).is_none() {
+            if over || self.playback.mode == Mode::Stepping {
+                self.playback.pause();
+            }
+            return None;
+        }
+        self.error = None;
+        let moves: Vec<String> = self.history.moves().iter().map(|m| uci(*m)).collect();
+        Some(Request {
+            id: self.playback.ask(),
+            generation: self.generation,
+            ply: self.history.plies(),
+            white: self.name(White),
+            black: self.name(Black),
+            start: to_fen(self.history.start()),
+            moves: moves.join(" "),
+        })
+    }
+
+    pub fn answer(&mut self, reply: Reply, now: f64) -> Answer {
+        if !self.playback.answered(reply.id, now) {
+            return Answer::Ignored;
+        }
+        if reply.generation != self.generation || reply.ply != self.history.plies() || !self.history.at_latest() {
+            return Answer::Stale;
+        }
+        if let Some(error) = reply.error {
+            self.error = Some(error);
+            self.playback.pause();
+            return Answer::Failed;
+        }
+        match reply.action.and_then(|a| parse_uci(self.game(), &a)) {
+            Some(act) => {
+                self.make_move(act);
+                if let Some(comment) = reply.comment {
+                    self.history.set_comment(comment);
+                }
+                Answer::Moved(act)
+            }
+            None => {
+                self.gave_up = Some(self.game().turn);
+                self.playback.pause();
+                Answer::GaveUp
+            }
+        }
+    }
+
+    // moving from an earlier position carries on the game from there
+    pub fn human_move(&mut self, act: Action) {
+        self.resume();
+        self.playback.play();
+        self.make_move(act);
+    }
+
+    fn make_move(&mut self, act: Action) {
+        if self.history.push(act).check_state().is_some() {
+            self.playback.pause();
+        }
+    }
+
+    // moving around the history stops the bots, play carries on from
+    // whatever is on the board
+    pub fn back(&mut self) {
+        self.playback.pause();
+        self.history.back();
+    }
+
+    pub fn forward(&mut self) {
+        self.playback.pause();
+        self.history.forward();
+    }
+
+    pub fn go_to(&mut self, ply: usize) {
+        self.playback.pause();
+        self.history.go_to(ply);
+    }
+
+    // everything the status says comes from here, so it always matches what
+    // is going on
+    pub fn status(&self) -> String {
+        let turn = self.game().turn;
+        if let Some(player) = self.gave_up.filter(|_| self.history.at_latest()) {
+            return format!("{:?} ({}) didn't come up with a move, so it's a draw", player, self.name(player));
+        }
+        if let Some(error) = self.error.as_ref().filter(|_| self.history.at_latest()) {
+            return format!("{:?} ({}) ran into a problem, play to try again: {}", turn, self.name(turn), error);
+        }
+        match State::from(self.game().check_state()) {
+            State::Playing if !self.history.at_latest() => format!(
+                "Looking at ply {} of {}, play or move to carry on from here",
+                self.history.viewing, self.history.plies()),
+            State::Playing if self.human_turn() => format!("{:?}'s turn, your move", turn),
+            State::Playing if self.playback.waiting.is_some() => format!("{:?} ({}) is thinking...", turn, self.name(turn)),
+            State::Playing if self.playback.mode == Mode::Paused => format!("{:?}'s turn, paused", turn),
+            State::Playing => format!("{:?}'s turn", turn),
+            State::Draw => "Game over: Draw".to_string(),
+            State::WinWhite => "White wins".to_string(),
+            State::WinBlack => "Black wins".to_string(),
+        }
+    }
+
+    // the reference evaluation of the position on the board, how much of the
+    // bar is white's and what it says
+    pub fn evaluation(&self) -> (f32, String) {
+        match self.game().check_state() {
+            Some(Win(White)) => (1.0, "1-0".to_string()),
+            Some(Win(Black)) => (0.0, "0-1".to_string()),
+            Some(Draw) => (0.5, "1/2-1/2".to_string()),
+            None => {
+                let score = commentary::evaluation(self.game());
+                (commentary::white_share(score), commentary::evaluation_text(score))
+            }
+        }
+    }
+
+    // the position on the board
+    pub fn fen(&self) -> String {
+        to_fen(self.game())
+    }
+
+    // the whole game, not just up to the position on the board
+    pub fn pgn(&self) -> String {
+        let result = match self.history.latest().check_state() {
+            Some(Win(White)) => "1-0",
+            Some(Win(Black)) => "0-1",
+            Some(Draw) => "1/2-1/2",
+            None if self.gave_up.is_some() => "1/2-1/2",
+            None => "*",
+        };
+        let tags = [
+            ("Event", "dumb_chess".to_string()),
+            ("Site", "?".to_string()),
+            ("Date", "????.??.??".to_string()),
+            ("Round", "-".to_string()),
+            ("White", self.name(White)),
+            ("Black", self.name(Black)),
+            ("Result", result.to_string()),
+        ];
+        write_pgn(&tags, self.history.start(), self.history.moves(), result)
+    }
+}
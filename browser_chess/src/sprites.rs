@@ -0,0 +1,61 @@
+use wasm_bindgen::prelude::*;
+
+use dumb_chess::{notation::piece_letter, piece::Piece, player::Player};
+
+// the piece set in pieces/, built into the wasm so there is nothing else to
+// serve, and it looks the same everywhere unlike the unicode pieces in
+// whatever font the system has
+const SVGS: [(&str, &str); 12] = [
+    ("wP", include_str!("../pieces/wP.svg")),
+    ("wN", include_str!("../pieces/wN.svg")),
+    ("wB", include_str!("../pieces/wB.svg")),
+    ("wR", include_str!("../pieces/wR.svg")),
+    ("wQ", include_str!("../pieces/wQ.svg")),
+    ("wK", include_str!("../pieces/wK.svg")),
+    ("bP", include_str!("../pieces/bP.svg")),
+    ("bN", include_str!("../pieces/bN.svg")),
+    ("bB", include_str!("../pieces/bB.svg")),
+    ("bR", include_str!("../pieces/bR.svg")),
+    ("bQ", include_str!("../pieces/bQ.svg")),
+    ("bK", include_str!("../pieces/bK.svg")),
+];
+
+// the file name for a piece, w or b and then the letter
+pub fn sprite_name(piece: Piece) -> Option<String> {
+    let color = match piece.owner()? {
+        Player::White => 'w',
+        Player::Black => 'b',
+    };
+    Some(format!("{}{}", color, piece_letter(piece).to_ascii_uppercase()))
+}
+
+pub fn svg(piece: Piece) -> Option<&'static str> {
+    let name = sprite_name(piece)?;
+    SVGS.iter().find(|(n, _)| *n == name).map(|(_, svg)| *svg)
+}
+
+pub struct Sprites {
+    images: Vec<(&'static str, web_sys::HtmlImageElement)>,
+}
+
+impl Sprites {
+    // the images load in the background, until then pieces just aren't drawn
+    pub fn load() -> Result<Self, JsValue> {
+        let mut images = Vec::new();
+        for (name, svg) in SVGS {
+            let image = web_sys::HtmlImageElement::new()?;
+            let encoded: String = js_sys::encode_uri_component(svg).into();
+            image.set_src(&format!("data:image/svg+xml;charset=utf-8,{}", encoded));
+            images.push((name, image));
+        }
+        Ok(Sprites { images })
+    }
+
+    pub fn get(&self, piece: Piece) -> Option<&web_sys::HtmlImageElement> {
+        let name = sprite_name(piece)?;
+        self.images.iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, image)| image)
+            .filter(|image| image.complete())
+    }
+}
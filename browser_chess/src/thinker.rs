@@ -0,0 +1,144 @@
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+
+use dumb_chess::{action::Action, bot::Bot, commentary, definition, game::ChessGame, notation::{from_fen, parse_uci, uci}, player::Player, registry::Registry};
+
+use crate::session::HUMAN;
+
+// what the page sends the worker (as json) when it wants a move
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Request {
+    pub id: u32,
+    pub generation: u32,
+    // how many plies had been played, so an answer for an old position can
+    // be told apart
+    pub ply: usize,
+    pub white: String,
+    pub black: String,
+    // a fen, and the moves since it in uci, space separated
+    pub start: String,
+    pub moves: String,
+}
+
+// and what comes back, no move is the bot giving up
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Reply {
+    pub id: u32,
+    pub generation: u32,
+    pub ply: usize,
+    pub action: Option<String>,
+    // why the bot thinks it's a good move, for bots that can say
+    #[serde(default)]
+    pub comment: Option<String>,
+    // the worker couldn't answer at all, see worker.js
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+// the bots, living in a web worker so a slow one doesn't freeze the page
+// the page sends the whole game every time it wants a move, and which game it
+// is (the generation), the bots are only made again when that changes so the
+// ones that remember things about the game get to remember them
+#[wasm_bindgen]
+pub struct Thinker {
+    registry: Registry,
+    generation: Option<u32>,
+    white: Option<Box<dyn Bot>>,
+    black: Option<Box<dyn Bot>>,
+}
+
+#[wasm_bindgen]
+impl Thinker {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        crate::utils::set_panic_hook();
+        Thinker { registry: Registry::new(), generation: None, white: None, black: None }
+    }
+
+    pub fn load_definitions(&mut self, text: &str) -> Result<(), JsValue> {
+        let defs = definition::parse(text).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        self.registry.register_definitions(defs);
+        // anything already made might be out of date
+        self.generation = None;
+        Ok(())
+    }
+
+    // a Request in, a Reply out
+    pub fn answer(&mut self, request: &str) -> Result<String, JsValue> {
+        let request: Request = serde_json::from_str(request).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let reply = self.reply(&request);
+        serde_json::to_string(&reply).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    // the move (in uci) for whoever's turn it is after the moves (uci, space
+    // separated) from the starting position (a fen), None for giving up
+    pub fn think(&mut self, generation: u32, white: &str, black: &str, start: &str, moves: &str) -> Option<String> {
+        self.decide(generation, white, black, start, moves).map(|(act, _)| uci(act))
+    }
+
+    fn new_game(&mut self, white: &str, black: &str) {
+        self.white = self.create(white);
+        self.black = self.create(black);
+        for bot in [&mut self.white, &mut self.black].into_iter().flatten() {
+            bot.new_game();
+        }
+        // nothing to learn about a person ahead of time
+        if let (Some(white), Some(black)) = (&mut self.white, &mut self.black) {
+            white.meet_opponent(black.as_ref());
+            black.meet_opponent(white.as_ref());
+        }
+    }
+
+    fn create(&self, name: &str) -> Option<Box<dyn Bot>> {
+        if name == HUMAN {
+            return None;
+        }
+        self.registry.create(name)
+    }
+}
+
+impl Thinker {
+    pub fn reply(&mut self, request: &Request) -> Reply {
+        let decision = self.decide(request.generation, &request.white, &request.black, &request.start, &request.moves);
+        Reply {
+            id: request.id,
+            generation: request.generation,
+            ply: request.ply,
+            action: decision.as_ref().map(|(act, _)| uci(*act)),
+            comment: decision.and_then(|(_, comment)| comment),
+            error: None,
+        }
+    }
+
+    // the move, and what the bot has to say about it
+    fn decide(&mut self, generation: u32, white: &str, black: &str, start: &str, moves: &str) -> Option<(Action, Option<String>)> {
+        if self.generation != Some(generation) {
+            self.new_game(white, black);
+            self.generation = Some(generation);
+        }
+        let game = replay(start, moves)?;
+        let bot = match game.turn {
+            Player::White => &mut self.white,
+            Player::Black => &mut self.black,
+        };
+        let bot = bot.as_mut()?;
+        let act = bot.choose(&game)?;
+        Some((act, commentary::comment(bot.as_ref(), &game, act)))
+    }
+}
+
+impl Default for Thinker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// None if the fen or any of the moves can't be played
+pub fn replay(start: &str, moves: &str) -> Option<ChessGame> {
+    let mut game = from_fen(start).ok()?;
+    for m in moves.split_whitespace() {
+        let act = parse_uci(&game, m)?;
+        game = game.step(act);
+    }
+    Some(game)
+}
@@ -0,0 +1,366 @@
+use std::time::Duration;
+
+use wasm_bindgen::prelude::*;
+
+use dumb_chess::{
+    adjudication::{Adjudicator, Rules},
+    archive::{result_text, ArchivedGame},
+    batch::{self, BatchError, Game, GameRecord},
+    definition,
+    game::{play_game_quietly, ChessGame},
+    notation::uci,
+    registry::Registry,
+    rng,
+};
+
+use crate::{fill_move_list, history::History, input::MoveInput, render_board, sprites::Sprites, window};
+
+// bots that never finish would hold up the whole tournament
+const MAX_PLIES: u32 = 400;
+
+// the games of a round robin between the named (comma separated) strategies,
+// the same pairings and seeds the cli batch command would use, but the games
+// themselves can differ since these get cut off at MAX_PLIES
+pub fn schedule(registry: &Registry, players: &str, rounds: u32, seed: u64) -> Result<Vec<Game>, BatchError> {
+    let players: Vec<String> = players.split(',')
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect();
+    if let Some(unknown) = players.iter().find(|name| !registry.contains(name)) {
+        return Err(BatchError::UnknownStrategy(unknown.clone()));
+    }
+    Ok(batch::round_robin(&players, rounds, seed))
+}
+
+// one game start to finish, there is no clock in here so the duration is left
+// for whoever called to fill in
+pub fn play(registry: &Registry, game: &Game) -> Result<GameRecord, BatchError> {
+    let create = |name: &String| registry.create(name).ok_or_else(|| BatchError::UnknownStrategy(name.clone()));
+    let mut white = create(&game.white)?;
+    let mut black = create(&game.black)?;
+    let mut judge = Adjudicator::new(Rules { max_plies: Some(MAX_PLIES), ..Rules::default() });
+    rng::seed(game.seed);
+    let (end, result) = play_game_quietly(black.as_mut(), white.as_mut(), &mut judge);
+    Ok(GameRecord {
+        white: game.white.clone(),
+        black: game.black.clone(),
+        seed: game.seed,
+        result,
+        moves: end.moves,
+        duration: Duration::ZERO,
+    })
+}
+
+// plays the tournament's games one at a time in a worker (see
+// tournament_worker.js), handing each back as a line of the cli's archive
+#[wasm_bindgen]
+pub struct Referee {
+    registry: Registry,
+    games: Vec<Game>,
+    next: usize,
+}
+
+#[wasm_bindgen]
+impl Referee {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        crate::utils::set_panic_hook();
+        Referee { registry: Registry::new(), games: Vec::new(), next: 0 }
+    }
+
+    pub fn load_definitions(&mut self, text: &str) -> Result<(), JsValue> {
+        let defs = definition::parse(text).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        self.registry.register_definitions(defs);
+        Ok(())
+    }
+
+    // how many games there are to play
+    pub fn start(&mut self, players: &str, rounds: u32, seed: u32) -> Result<usize, JsValue> {
+        self.games = schedule(&self.registry, players, rounds, seed as u64)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        self.next = 0;
+        Ok(self.games.len())
+    }
+
+    // the next game played as json, None once they have all been played and
+    // an error when the game can't be played, like a bot that is gone
+    pub fn play_next(&mut self) -> Result<Option<String>, JsValue> {
+        let Some(game) = self.games.get(self.next) else {
+            return Ok(None);
+        };
+        let start = js_sys::Date::now();
+        let record = play(&self.registry, game).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        self.next += 1;
+        // ArchivedGame::new asks the system clock, which wasm doesn't have
+        let archived = ArchivedGame {
+            white: record.white,
+            black: record.black,
+            seed: record.seed,
+            moves: record.moves.iter().map(|m| uci(*m)).collect(),
+            result: record.result,
+            plies: record.moves.len(),
+            duration_ms: (js_sys::Date::now() - start) as u64,
+            played_at: (start / 1000.0) as u64,
+        };
+        serde_json::to_string(&archived).map(Some).map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+}
+
+impl Default for Referee {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct Elements {
+    canvas: web_sys::HtmlCanvasElement,
+    status: web_sys::HtmlElement,
+    players: web_sys::HtmlElement,
+    standings: web_sys::HtmlElement,
+    cross_table: web_sys::HtmlElement,
+    games: web_sys::HtmlElement,
+    move_list: web_sys::HtmlElement,
+}
+
+// the results so far, and any finished game played back on the board
+#[wasm_bindgen]
+pub struct TournamentPage {
+    page: Elements,
+    registry: Registry,
+    checkboxes: Vec<web_sys::HtmlInputElement>,
+    players: Vec<String>,
+    total: usize,
+    games: Vec<ArchivedGame>,
+    // the games read back once as they come in, the tables are made from
+    // these every time a game is added
+    records: Vec<GameRecord>,
+    // which game is on the board
+    replay: Option<(usize, History)>,
+    sprites: Sprites,
+}
+
+#[wasm_bindgen]
+impl TournamentPage {
+    pub fn load_definitions(&mut self, text: &str) -> Result<(), JsValue> {
+        let defs = definition::parse(text).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        let mut added: Vec<String> = Vec::new();
+        for def in &defs {
+            if !self.registry.contains(&def.name) && !added.contains(&def.name) {
+                added.push(def.name.clone());
+            }
+        }
+        self.registry.register_definitions(defs);
+        for name in added {
+            self.add_checkbox(&name)?;
+        }
+        Ok(())
+    }
+
+    // the ticked strategies, comma separated
+    pub fn selected(&self) -> String {
+        self.checkboxes.iter()
+            .filter(|c| c.checked())
+            .map(|c| c.value())
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    pub fn start(&mut self, players: &str, total: usize) {
+        self.players = players.split(',').map(|p| p.to_string()).collect();
+        self.total = total;
+        self.games.clear();
+        self.records.clear();
+        self.replay = None;
+        self.page.status.set_text_content(Some(&format!("Playing {} games", total)));
+        let _ = self.show_results();
+        self.show_replay();
+    }
+
+    pub fn add_game(&mut self, json: &str) -> Result<(), JsValue> {
+        let game: ArchivedGame = serde_json::from_str(json).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        self.records.extend(game.record());
+        self.games.push(game);
+        let played = self.games.len();
+        let text = if played == self.total {
+            format!("Played all {} games, click one to watch it", played)
+        } else {
+            format!("Played {} of {} games", played, self.total)
+        };
+        self.page.status.set_text_content(Some(&text));
+        self.show_results()
+    }
+
+    pub fn show_error(&self, message: &str) {
+        self.page.status.set_text_content(Some(message));
+    }
+
+    pub fn show_game(&mut self, index: usize) {
+        let Some(actions) = self.games.get(index).and_then(|g| g.actions()) else {
+            return;
+        };
+        let mut history = History::new(ChessGame::new());
+        for act in actions {
+            history.push(act);
+        }
+        history.go_to(0);
+        self.replay = Some((index, history));
+        let _ = self.show_results();
+        self.show_replay();
+    }
+
+    pub fn back(&mut self) {
+        if let Some((_, history)) = &mut self.replay {
+            history.back();
+        }
+        self.show_replay();
+    }
+
+    pub fn forward(&mut self) {
+        if let Some((_, history)) = &mut self.replay {
+            history.forward();
+        }
+        self.show_replay();
+    }
+
+    pub fn go_to(&mut self, ply: usize) {
+        if let Some((_, history)) = &mut self.replay {
+            history.go_to(ply);
+        }
+        self.show_replay();
+    }
+
+    pub fn render(&self) {
+        let start = ChessGame::new();
+        let game = self.replay.as_ref().map_or(&start, |(_, history)| history.shown());
+        render_board(&self.page.canvas, game, &MoveInput::default(), &self.sprites, false);
+    }
+
+    fn show_replay(&self) {
+        self.page.move_list.set_text_content(None);
+        if let Some((_, history)) = &self.replay {
+            let _ = fill_move_list(&self.page.move_list, history);
+        }
+        self.render();
+    }
+
+    fn add_checkbox(&mut self, name: &str) -> Result<(), JsValue> {
+        let document = window().document().expect("Windows should have a document");
+        let label = document.create_element("label")?;
+        let checkbox: web_sys::HtmlInputElement = document.create_element("input")?.dyn_into()?;
+        checkbox.set_type("checkbox");
+        checkbox.set_value(name);
+        checkbox.set_checked(true);
+        label.append_child(&checkbox)?;
+        label.append_child(&document.create_text_node(name))?;
+        self.page.players.append_child(&label)?;
+        self.checkboxes.push(checkbox);
+        Ok(())
+    }
+
+    fn show_results(&self) -> Result<(), JsValue> {
+        self.fill_standings(&self.records)?;
+        self.fill_cross_table(&self.records)?;
+        self.fill_games()
+    }
+
+    fn fill_standings(&self, records: &[GameRecord]) -> Result<(), JsValue> {
+        let standings = batch::standings(records);
+        let ratings = batch::ratings(records);
+        let table = &self.page.standings;
+        table.set_text_content(None);
+        add_row(table, "th", &["", "Strategy", "Games", "Wins", "Draws", "Losses", "Score", "Elo"])?;
+        for (i, (name, rating)) in ratings.iter().enumerate() {
+            let tally = standings.iter().find(|(n, _)| n == name).map(|(_, t)| t.clone()).unwrap_or_default();
+            add_row(table, "td", &[
+                &(i + 1).to_string(),
+                name,
+                &tally.games().to_string(),
+                &tally.wins.to_string(),
+                &tally.draws.to_string(),
+                &tally.losses.to_string(),
+                &format!("{:.1}", tally.score()),
+                &format!("{:+.0}", rating),
+            ])?;
+        }
+        Ok(())
+    }
+
+    // white's score against each opponent, same as the cli prints
+    fn fill_cross_table(&self, records: &[GameRecord]) -> Result<(), JsValue> {
+        let scores = batch::cross_table(records);
+        let table = &self.page.cross_table;
+        table.set_text_content(None);
+        let mut header = vec!["White \\ Black".to_string()];
+        header.extend(self.players.iter().cloned());
+        add_row(table, "th", &header.iter().map(|h| h.as_str()).collect::<Vec<_>>())?;
+        for white in &self.players {
+            let mut row = vec![white.clone()];
+            for black in &self.players {
+                row.push(match scores.get(&(white.clone(), black.clone())) {
+                    Some(tally) => format!("{:.1}", tally.score()),
+                    None => "-".to_string(),
+                });
+            }
+            add_row(table, "td", &row.iter().map(|c| c.as_str()).collect::<Vec<_>>())?;
+        }
+        Ok(())
+    }
+
+    // clicking one puts it on the board
+    fn fill_games(&self) -> Result<(), JsValue> {
+        let document = window().document().expect("Windows should have a document");
+        let list = &self.page.games;
+        list.set_text_content(None);
+        let shown = self.replay.as_ref().map(|(i, _)| *i);
+        for (i, game) in self.games.iter().enumerate() {
+            let line = document.create_element("div")?;
+            line.set_text_content(Some(&format!("{}. {} vs {}: {} ({:?}) in {} plies",
+                i + 1, game.white, game.black, result_text(game.result), game.result.reason, game.plies)));
+            line.set_attribute("data-game", &i.to_string())?;
+            line.set_class_name(if shown == Some(i) { "game current" } else { "game" });
+            list.append_child(&line)?;
+        }
+        Ok(())
+    }
+}
+
+fn add_row(table: &web_sys::HtmlElement, cell: &str, values: &[&str]) -> Result<(), JsValue> {
+    let document = window().document().expect("Windows should have a document");
+    let row = document.create_element("tr")?;
+    for value in values {
+        let element = document.create_element(cell)?;
+        element.set_text_content(Some(value));
+        row.append_child(&element)?;
+    }
+    table.append_child(&row)?;
+    Ok(())
+}
+
+#[wasm_bindgen]
+pub fn setup_tournament(canvas: web_sys::HtmlCanvasElement,
+                        status: web_sys::HtmlElement,
+                        players: web_sys::HtmlElement,
+                        standings: web_sys::HtmlElement,
+                        cross_table: web_sys::HtmlElement,
+                        games: web_sys::HtmlElement,
+                        move_list: web_sys::HtmlElement)
+    -> TournamentPage {
+
+    crate::utils::set_panic_hook();
+    let mut page = TournamentPage {
+        page: Elements { canvas, status, players, standings, cross_table, games, move_list },
+        registry: Registry::new(),
+        checkboxes: Vec::new(),
+        players: Vec::new(),
+        total: 0,
+        games: Vec::new(),
+        records: Vec::new(),
+        replay: None,
+        sprites: Sprites::load().expect("should be able to make images"),
+    };
+    let names: Vec<String> = page.registry.entries().map(|e| e.name().to_string()).collect();
+    for name in names {
+        let _ = page.add_checkbox(&name);
+    }
+    page
+}
@@ -0,0 +1,74 @@
+use browser_chess::history::History;
+use dumb_chess::game::ChessGame;
+use dumb_chess::notation::{parse_uci, to_fen};
+
+fn played(moves: &[&str]) -> History {
+    let mut history = History::new(ChessGame::new());
+    for m in moves {
+        let act = parse_uci(history.latest(), m).unwrap();
+        history.push(act);
+    }
+    history
+}
+
+#[test]
+fn moves_in_san() {
+    let history = played(&["e2e4", "e7e5", "g1f3", "b8c6", "f1b5"]);
+    assert_eq!(history.sans(), ["e4", "e5", "Nf3", "Nc6", "Bb5"]);
+    assert_eq!(history.plies(), 5);
+    assert!(history.at_latest());
+}
+
+#[test]
+fn navigating() {
+    let mut history = played(&["e2e4", "e7e5", "g1f3"]);
+    history.back();
+    history.back();
+    assert_eq!(history.viewing, 1);
+    assert_eq!(to_fen(history.shown()), "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+    // the later moves are still there until something else is played
+    assert_eq!(history.plies(), 3);
+    history.forward();
+    history.forward();
+    history.forward();
+    assert!(history.at_latest());
+    history.go_to(0);
+    history.back();
+    assert_eq!(history.viewing, 0);
+    assert_eq!(to_fen(history.shown()), to_fen(history.start()));
+    history.go_to(100);
+    assert_eq!(history.viewing, 3);
+}
+
+#[test]
+fn playing_from_earlier() {
+    let mut history = played(&["e2e4", "e7e5", "g1f3"]);
+    history.go_to(1);
+    let act = parse_uci(history.shown(), "c7c5").unwrap();
+    history.push(act);
+    assert_eq!(history.sans(), ["e4", "c5"]);
+    assert!(history.at_latest());
+    assert_eq!(history.latest().moves.len(), 2);
+
+    // nothing is lost by resuming at the end
+    assert!(!history.resume());
+    history.back();
+    assert!(history.resume());
+    assert_eq!(history.sans(), ["e4"]);
+}
+
+#[test]
+fn comments_follow_the_moves() {
+    let mut history = played(&["e2e4"]);
+    history.set_comment("Ape: moved 2 squares".to_string());
+    let act = parse_uci(history.latest(), "e7e5").unwrap();
+    history.push(act);
+    assert_eq!(history.comments(), [Some("Ape: moved 2 squares".to_string()), None]);
+    history.back();
+    assert_eq!(history.comment(), Some("Ape: moved 2 squares"));
+    // playing something else forgets the comments on the moves undone
+    history.go_to(0);
+    let act = parse_uci(history.shown(), "d2d4").unwrap();
+    history.push(act);
+    assert_eq!(history.comments(), [None]);
+}
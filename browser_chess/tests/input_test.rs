@@ -0,0 +1,72 @@
+use browser_chess::input::MoveInput;
+use dumb_chess::coord::Coord;
+use dumb_chess::game::ChessGame;
+use dumb_chess::notation::{from_fen, parse_square, uci};
+
+fn sq(name: &str) -> Coord {
+    parse_square(name).unwrap()
+}
+
+#[test]
+fn click_then_click() {
+    let game = ChessGame::new();
+    let mut input = MoveInput::default();
+    assert_eq!(input.press(&game, sq("e2"), (0.0, 0.0)), None);
+    assert_eq!(input.release(&game, sq("e2")), None);
+    assert_eq!(input.selected, Some(sq("e2")));
+    assert_eq!(input.targets(&game).len(), 2);
+
+    // somewhere it can't go clears the selection
+    assert_eq!(input.press(&game, sq("e5"), (0.0, 0.0)), None);
+    assert_eq!(input.selected, None);
+
+    // the other side's pieces can't be picked up
+    input.press(&game, sq("e7"), (0.0, 0.0));
+    assert_eq!(input.selected, None);
+
+    input.press(&game, sq("g1"), (0.0, 0.0));
+    input.release(&game, sq("g1"));
+    let act = input.press(&game, sq("f3"), (0.0, 0.0)).unwrap();
+    assert_eq!(uci(act), "g1f3");
+    assert_eq!(input, MoveInput::default());
+}
+
+#[test]
+fn drag_and_drop() {
+    let game = ChessGame::new();
+    let mut input = MoveInput::default();
+    input.press(&game, sq("d2"), (175.0, 325.0));
+    input.drag((180.0, 230.0));
+    assert_eq!(input.dragging, Some((180.0, 230.0)));
+    let act = input.release(&game, sq("d4")).unwrap();
+    assert_eq!(uci(act), "d2d4");
+
+    // dropping somewhere illegal keeps the piece selected
+    input.press(&game, sq("d2"), (0.0, 0.0));
+    assert_eq!(input.release(&game, sq("d5")), None);
+    assert_eq!(input.selected, Some(sq("d2")));
+    assert_eq!(input.dragging, None);
+}
+
+#[test]
+fn promotion_picker() {
+    let game = from_fen("8/4P3/8/8/8/k7/8/4K3 w - - 0 1").unwrap();
+    let mut input = MoveInput::default();
+    input.press(&game, sq("e7"), (0.0, 0.0));
+    assert_eq!(input.release(&game, sq("e8")), None);
+    let choices = input.promotion_squares(&game);
+    assert_eq!(choices.len(), 4);
+    // queen on the promotion square, the rest going back down the file
+    assert_eq!(choices[0].0, sq("e8"));
+    assert_eq!(uci(choices[0].1), "e7e8q");
+    assert_eq!(choices[3].0, sq("e5"));
+
+    let act = input.press(&game, sq("e7"), (0.0, 0.0)).unwrap();
+    assert_eq!(uci(act), uci(choices[1].1));
+
+    // clicking away backs out
+    input.press(&game, sq("e7"), (0.0, 0.0));
+    input.release(&game, sq("e8"));
+    assert_eq!(input.press(&game, sq("a1"), (0.0, 0.0)), None);
+    assert_eq!(input, MoveInput::default());
+}
@@ -0,0 +1,48 @@
+use browser_chess::playback::{Mode, Playback};
+
+#[test]
+fn waiting_between_moves() {
+    let mut playback = Playback::new(100.0);
+    assert!(playback.due(0.0));
+    let id = playback.ask();
+    // one question at a time
+    assert!(!playback.due(1000.0));
+    assert!(!playback.answered(id + 1, 0.0));
+    assert!(playback.answered(id, 50.0));
+    assert!(!playback.due(100.0));
+    assert!(playback.due(150.0));
+
+    playback.pause();
+    assert!(!playback.due(1000.0));
+    playback.toggle();
+    assert_eq!(playback.mode, Mode::Playing);
+}
+
+#[test]
+fn stepping_makes_one_move() {
+    let mut playback = Playback::new(1000.0);
+    playback.pause();
+    playback.step();
+    // no waiting for the delay
+    assert!(playback.due(0.0));
+    let id = playback.ask();
+    assert!(playback.answered(id, 0.0));
+    assert_eq!(playback.mode, Mode::Paused);
+    assert!(!playback.due(5000.0));
+}
+
+#[test]
+fn stale_answers() {
+    let mut playback = Playback::new(0.0);
+    let old = playback.ask();
+    // the game got reset while the worker was thinking
+    playback.forget();
+    let new = playback.ask();
+    assert!(!playback.answered(old, 0.0));
+    assert_eq!(playback.waiting, Some(new));
+
+    playback.cancel();
+    assert_eq!(playback.waiting, None);
+    assert_eq!(playback.mode, Mode::Paused);
+    assert!(!playback.answered(new, 0.0));
+}
@@ -0,0 +1,158 @@
+use browser_chess::playback::Mode;
+use browser_chess::session::{read_game, seat_for, Answer, Session, State, HUMAN};
+use browser_chess::thinker::{Reply, Thinker};
+use dumb_chess::final_state::FinalState::*;
+use dumb_chess::game::ChessGame;
+use dumb_chess::notation::{parse_uci, to_fen};
+use dumb_chess::player::Player::*;
+
+fn bots() -> Session {
+    Session::new(Some("Random".to_string()), Some("Random".to_string()))
+}
+
+fn reply_to(session: &Session, id: u32, action: Option<&str>) -> Reply {
+    Reply { id, generation: session.generation, ply: session.history.plies(), action: action.map(|a| a.to_string()), comment: None, error: None }
+}
+
+#[test]
+fn states() {
+    assert_eq!(State::from(None), State::Playing);
+    assert_eq!(State::from(Some(Draw)), State::Draw);
+    assert_eq!(State::from(Some(Win(White))), State::WinWhite);
+    assert_eq!(State::from(Some(Win(Black))), State::WinBlack);
+}
+
+#[test]
+fn seats() {
+    assert_eq!(seat_for(HUMAN), None);
+    assert_eq!(seat_for("Random").as_deref(), Some("Random"));
+    let session = Session::new(None, Some("Random".to_string()));
+    assert_eq!(session.name(White), HUMAN);
+    assert_eq!(session.name(Black), "Random");
+}
+
+#[test]
+fn bots_play_through_the_worker() {
+    let mut session = bots();
+    let mut thinker = Thinker::new();
+    for ply in 0..6 {
+        let request = session.tick(ply as f64 * 1000.0).unwrap();
+        assert_eq!(request.ply, ply);
+        // still thinking, nothing more to ask
+        assert!(session.tick(ply as f64 * 1000.0 + 500.0).is_none());
+        assert!(session.status().contains("thinking"));
+        let reply = thinker.reply(&request);
+        assert!(matches!(session.answer(reply, ply as f64 * 1000.0), Answer::Moved(_)));
+    }
+    assert_eq!(session.history.plies(), 6);
+}
+
+#[test]
+fn answers_for_another_game_are_dropped() {
+    let mut session = bots();
+    let request = session.tick(0.0).unwrap();
+    let old = reply_to(&session, request.id, Some("e2e4"));
+    session.reset(Some("Random".to_string()), Some("Random".to_string()));
+    assert_eq!(session.answer(old, 0.0), Answer::Ignored);
+
+    // the board got moved back while the bot was thinking
+    let request = session.tick(0.0).unwrap();
+    session.answer(reply_to(&session, request.id, Some("e2e4")), 0.0);
+    let request = session.tick(1000.0).unwrap();
+    let reply = reply_to(&session, request.id, Some("e7e5"));
+    session.back();
+    assert_eq!(session.answer(reply, 1000.0), Answer::Stale);
+    assert_eq!(session.history.plies(), 1);
+    assert_eq!(session.playback.mode, Mode::Paused);
+}
+
+#[test]
+fn giving_up_is_a_draw() {
+    let mut session = bots();
+    let request = session.tick(0.0).unwrap();
+    assert_eq!(session.answer(reply_to(&session, request.id, None), 0.0), Answer::GaveUp);
+    assert_eq!(session.gave_up, Some(White));
+    assert!(session.over());
+    assert!(session.status().contains("didn't come up with a move"));
+    assert!(session.pgn().contains("[Result \"1/2-1/2\"]"));
+}
+
+#[test]
+fn worker_errors_pause_the_game() {
+    let mut session = bots();
+    let request = session.tick(0.0).unwrap();
+    let mut reply = reply_to(&session, request.id, None);
+    reply.error = Some("unreachable".to_string());
+    assert_eq!(session.answer(reply, 0.0), Answer::Failed);
+    assert!(!session.over());
+    assert_eq!(session.playback.mode, Mode::Paused);
+    assert!(session.status().contains("ran into a problem"));
+    // and asks again once played
+    session.playback.play();
+    assert_eq!(session.tick(1000.0).unwrap().ply, 0);
+    assert!(session.status().contains("thinking"));
+}
+
+#[test]
+fn people_move_with_the_mouse() {
+    let mut session = Session::new(None, Some("Random".to_string()));
+    assert!(session.human_turn());
+    // nothing to ask the worker
+    assert!(session.tick(0.0).is_none());
+    assert!(session.status().contains("your move"));
+    let act = parse_uci(session.game(), "e2e4").unwrap();
+    session.human_move(act);
+    assert!(!session.human_turn());
+    assert_eq!(session.tick(0.0).unwrap().moves, "e2e4");
+}
+
+#[test]
+fn reading_games() {
+    let loaded = read_game("7k/6Q1/8/8/8/8/8/K7 b - - 0 1").unwrap();
+    assert!(loaded.moves.is_empty());
+    assert_eq!(loaded.white, None);
+
+    let loaded = read_game("[White \"Human\"]\n[Black \"Ape\"]\n\n1. e4 e5 2. Nf3 *").unwrap();
+    assert_eq!(loaded.moves.len(), 3);
+    assert_eq!(loaded.white.as_deref(), Some(HUMAN));
+    assert_eq!(loaded.black.as_deref(), Some("Ape"));
+    assert!(read_game("1. e5 *").is_err());
+
+    let mut session = bots();
+    session.start_from(loaded.start, loaded.moves, None, seat_for("Random"));
+    // loaded games wait to be looked over
+    assert_eq!(session.playback.mode, Mode::Paused);
+    assert!(session.pgn().contains("1. e4 e5 2. Nf3 *"));
+    assert_eq!(session.fen(), "rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2");
+    session.go_to(0);
+    assert_eq!(session.fen(), to_fen(&ChessGame::new()));
+    assert!(session.status().starts_with("Looking at ply 0 of 3"));
+}
+
+#[test]
+fn comments_and_evaluation() {
+    let mut session = Session::new(Some("Swarm".to_string()), Some("Random".to_string()));
+    let mut thinker = Thinker::new();
+    assert_eq!(session.evaluation(), (0.5, "0".to_string()));
+    let request = session.tick(0.0).unwrap();
+    let reply = thinker.reply(&request);
+    assert!(reply.comment.as_deref().unwrap().starts_with("Swarm: "));
+    session.answer(reply, 0.0);
+    assert!(session.history.comment().unwrap().starts_with("Swarm: "));
+    // random moves don't come with a reason
+    let request = session.tick(1000.0).unwrap();
+    session.answer(thinker.reply(&request), 1000.0);
+    assert_eq!(session.history.comment(), None);
+    session.back();
+    assert!(session.history.comment().is_some());
+    session.go_to(0);
+    assert_eq!(session.history.comment(), None);
+
+    let loaded = read_game("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+    session.start_from(loaded.start, loaded.moves, None, None);
+    let (share, text) = session.evaluation();
+    assert!(share > 0.5);
+    assert_eq!(text, "+2");
+    session.human_move(parse_uci(session.game(), "a1a8").unwrap());
+    assert_eq!(session.evaluation(), (1.0, "1-0".to_string()));
+}
@@ -0,0 +1,21 @@
+use browser_chess::sprites::{sprite_name, svg};
+use dumb_chess::piece::Piece::*;
+use dumb_chess::player::Player::*;
+
+#[test]
+fn every_piece_has_a_sprite() {
+    let pieces = [
+        Pawn(0, White), Knight(0, White), Bishop(0, White), Rook(0, White), Queen(0, White), King(0, White),
+        Pawn(0, Black), Knight(0, Black), Bishop(0, Black), Rook(0, Black), Queen(0, Black), King(0, Black),
+    ];
+    let mut seen = Vec::new();
+    for piece in pieces {
+        let svg = svg(piece).unwrap();
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(!seen.contains(&svg));
+        seen.push(svg);
+    }
+    assert_eq!(sprite_name(Knight(3, Black)).unwrap(), "bN");
+    assert_eq!(sprite_name(Empty), None);
+    assert_eq!(svg(Empty), None);
+}
@@ -0,0 +1,59 @@
+use browser_chess::thinker::{replay, Request, Thinker};
+use dumb_chess::game::ChessGame;
+use dumb_chess::notation::{parse_uci, to_fen};
+
+fn start() -> String {
+    to_fen(&ChessGame::new())
+}
+
+#[test]
+fn replaying_moves() {
+    let game = replay(&start(), "e2e4 e7e5 g1f3").unwrap();
+    assert_eq!(to_fen(&game), "rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2");
+    assert!(replay(&start(), "").is_some());
+    // black can't move first
+    assert!(replay(&start(), "e7e5").is_none());
+}
+
+#[test]
+fn thinking_gives_a_legal_move() {
+    let mut thinker = Thinker::new();
+    let moves = "e2e4 e7e5";
+    let answer = thinker.think(1, "Random", "Random", &start(), moves).unwrap();
+    let game = replay(&start(), moves).unwrap();
+    assert!(parse_uci(&game, &answer).is_some());
+}
+
+#[test]
+fn people_are_not_thought_for() {
+    let mut thinker = Thinker::new();
+    assert_eq!(thinker.think(1, "Human", "Random", &start(), ""), None);
+    assert!(thinker.think(1, "Human", "Random", &start(), "e2e4").is_some());
+}
+
+#[test]
+fn thinking_from_a_position() {
+    let mut thinker = Thinker::new();
+    // black has one move, taking the queen
+    let fen = "7k/6Q1/8/8/8/8/8/K7 b - - 0 1";
+    assert_eq!(thinker.think(1, "Random", "Random", fen, "").as_deref(), Some("h8g7"));
+    assert_eq!(to_fen(&replay(fen, "h8g7").unwrap()), "8/6k1/8/8/8/8/8/K7 w - - 0 2");
+}
+
+#[test]
+fn requests_and_replies() {
+    let mut thinker = Thinker::new();
+    let request = Request {
+        id: 7,
+        generation: 2,
+        ply: 1,
+        white: "Random".to_string(),
+        black: "Random".to_string(),
+        start: start(),
+        moves: "e2e4".to_string(),
+    };
+    let reply = thinker.reply(&request);
+    assert_eq!((reply.id, reply.generation, reply.ply), (7, 2, 1));
+    let game = replay(&start(), "e2e4").unwrap();
+    assert!(parse_uci(&game, reply.action.as_deref().unwrap()).is_some());
+}
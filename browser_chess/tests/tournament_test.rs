@@ -0,0 +1,32 @@
+use browser_chess::tournament::{play, schedule};
+use dumb_chess::batch::{self, BatchError};
+use dumb_chess::registry::Registry;
+
+#[test]
+fn scheduling() {
+    let registry = Registry::new();
+    let games = schedule(&registry, "Random, Swarm,Sleepy", 1, 4).unwrap();
+    let players: Vec<String> = ["Random", "Swarm", "Sleepy"].iter().map(|s| s.to_string()).collect();
+    // the same games the cli would play
+    assert_eq!(games, batch::round_robin(&players, 1, 4));
+    assert!(matches!(schedule(&registry, "Random,Nobody", 1, 4), Err(BatchError::UnknownStrategy(name)) if name == "Nobody"));
+}
+
+#[test]
+fn games_play_the_same_every_time() {
+    let registry = Registry::new();
+    let games = schedule(&registry, "Random,Swarm", 1, 9).unwrap();
+    for game in &games {
+        let first = play(&registry, game).unwrap();
+        let second = play(&registry, game).unwrap();
+        assert_eq!(first.moves, second.moves);
+        assert_eq!(first.result, second.result);
+        assert!(first.moves.len() <= 400);
+    }
+}
+
+#[test]
+fn missing_bots_are_an_error() {
+    let games = schedule(&Registry::new(), "Random,Swarm", 1, 9).unwrap();
+    assert!(matches!(play(&Registry::empty(), &games[0]), Err(BatchError::UnknownStrategy(_))));
+}
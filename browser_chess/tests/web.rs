@@ -0,0 +1,48 @@
+// the parts that need javascript to run, in node
+// wasm-pack test --node
+#![cfg(target_arch = "wasm32")]
+
+use wasm_bindgen_test::*;
+
+use browser_chess::thinker::{Reply, Request, Thinker};
+use browser_chess::tournament::Referee;
+use dumb_chess::archive::ArchivedGame;
+use dumb_chess::game::ChessGame;
+use dumb_chess::notation::to_fen;
+
+#[wasm_bindgen_test]
+fn answering_requests() {
+    let mut thinker = Thinker::new();
+    let request = Request {
+        id: 3,
+        generation: 1,
+        ply: 0,
+        white: "Random".to_string(),
+        black: "Random".to_string(),
+        start: to_fen(&ChessGame::new()),
+        moves: String::new(),
+    };
+    let reply: Reply = serde_json::from_str(&thinker.answer(&serde_json::to_string(&request).unwrap()).unwrap()).unwrap();
+    assert_eq!((reply.id, reply.generation, reply.ply), (3, 1, 0));
+    assert!(reply.action.is_some());
+    assert!(thinker.answer("not json").is_err());
+}
+
+#[wasm_bindgen_test]
+fn bad_definitions() {
+    let mut thinker = Thinker::new();
+    assert!(thinker.load_definitions("name = ").is_err());
+    let mut referee = Referee::new();
+    assert!(referee.load_definitions("{").is_err());
+}
+
+#[wasm_bindgen_test]
+fn refereeing() {
+    let mut referee = Referee::new();
+    assert!(referee.start("Random,Nobody", 1, 1).is_err());
+    assert_eq!(referee.start("Random, Ape", 1, 1).unwrap(), 2);
+    let first: ArchivedGame = serde_json::from_str(&referee.play_next().unwrap().unwrap()).unwrap();
+    assert!(first.record().is_some());
+    assert!(referee.play_next().unwrap().is_some());
+    assert!(referee.play_next().unwrap().is_none());
+}
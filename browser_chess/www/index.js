@@ -4,51 +4,203 @@ const canvas = document.getElementById('canvas');
 const white_select = document.getElementById('white');
 const black_select = document.getElementById('black');
 const play_stop = document.getElementById('play_stop');
+const step = document.getElementById('step');
 const reset = document.getElementById('reset');
+const cancel = document.getElementById('cancel');
+const flip = document.getElementById('flip');
 const status = document.getElementById('status');
 const descriptions = document.getElementById('descriptions');
-const speed = document.getElementById('speed');
+const delay = document.getElementById('delay');
+const definitions = document.getElementById('definitions');
+const moves = document.getElementById('moves');
+const analysis = document.getElementById('analysis');
+const back = document.getElementById('back');
+const forward = document.getElementById('forward');
+const notation = document.getElementById('notation');
+const load = document.getElementById('load');
+const copy_fen = document.getElementById('copy_fen');
+const copy_pgn = document.getElementById('copy_pgn');
+const download_pgn = document.getElementById('download_pgn');
+const share = document.getElementById('share');
 
-var game = setup(canvas, status, white_select, black_select, descriptions);
+var game = setup(canvas, status, white_select, black_select, descriptions, moves, analysis);
+game.set_delay(Number(delay.value));
 
-reset.addEventListener("click", (event) => {
-    handleReset();
-})
+// the bots choose their moves in a worker, the game says when to ask and
+// what, this just passes the messages along
+// a fresh worker needs to be told about any strategy files again
+var worker = null;
+var loaded_definitions = [];
+
+function startWorker() {
+    worker = new Worker(new URL('./worker.js', import.meta.url));
+    worker.onmessage = (event) => game.answer(event.data);
+    for (const text of loaded_definitions) {
+        worker.postMessage({type: "definitions", text: text});
+    }
+}
+
+// throws away whatever it's thinking about
+function restartWorker() {
+    worker.terminate();
+    startWorker();
+}
+
+startWorker();
 
 play_stop.addEventListener("click", (event) => {
-    handlePlayStop()
+    game.toggle_play();
 });
 
-speed.addEventListener("input", (event) => {
-    fps = speed.value;
+step.addEventListener("click", (event) => {
+    game.step();
 });
 
-var fps = speed.value;
-var playing = true;
-var animation_id = null;
-
-function handleReset() {
+reset.addEventListener("click", (event) => {
+    if (game.thinking()) {
+        restartWorker();
+    }
     game.reset();
-    playing = true;
+});
+
+cancel.addEventListener("click", (event) => {
+    if (game.thinking()) {
+        restartWorker();
+    }
+    game.cancel();
+});
+
+flip.addEventListener("click", (event) => {
+    game.flip();
+});
+
+delay.addEventListener("input", (event) => {
+    game.set_delay(Number(delay.value));
+});
+
+// pointer events cover both the mouse and touch screens
+canvas.addEventListener("pointerdown", (event) => {
+    canvas.setPointerCapture(event.pointerId);
+    game.mouse_down(event.offsetX, event.offsetY);
+});
+
+canvas.addEventListener("pointermove", (event) => {
+    game.mouse_move(event.offsetX, event.offsetY);
+});
+
+canvas.addEventListener("pointerup", (event) => {
+    game.mouse_up(event.offsetX, event.offsetY);
+});
+
+// looking back through the game stops the bots, play carries on from there
+moves.addEventListener("click", (event) => {
+    const ply = event.target.dataset.ply;
+    if (ply !== undefined) {
+        game.go_to(Number(ply));
+    }
+});
+
+back.addEventListener("click", (event) => {
+    game.back();
+});
+
+forward.addEventListener("click", (event) => {
+    game.forward();
+});
+
+document.addEventListener("keydown", (event) => {
+    if (["INPUT", "SELECT", "TEXTAREA"].includes(event.target.tagName)) {
+        return;
+    }
+    if (event.key === "ArrowLeft") {
+        game.back();
+    } else if (event.key === "ArrowRight") {
+        game.forward();
+    }
+});
+
+definitions.addEventListener("change", async (event) => {
+    for (const file of definitions.files) {
+        try {
+            const text = await file.text();
+            game.load_definitions(text);
+            loaded_definitions.push(text);
+            worker.postMessage({type: "definitions", text: text});
+        } catch (e) {
+            status.textContent = "Could not load " + file.name + ": " + e;
+        }
+    }
+});
+
+// starts from a fen or plays through a pgn
+function loadText(text) {
+    if (game.thinking()) {
+        restartWorker();
+    }
+    try {
+        game.load(text);
+    } catch (e) {
+        status.textContent = "Could not load that: " + e;
+    }
 }
 
-function handlePlayStop() {
-    playing = !playing;
+// #fen=... or #pgn=... in the address, which is what share copies
+function loadFragment() {
+    const params = new URLSearchParams(location.hash.slice(1));
+    const text = params.get("fen") ?? params.get("pgn");
+    if (text !== null) {
+        loadText(text);
+    }
 }
 
+// the text also goes in the box in case the clipboard isn't allowed
+function copyText(text, what) {
+    notation.value = text;
+    navigator.clipboard.writeText(text)
+        .then(() => status.textContent = "Copied the " + what)
+        .catch(() => status.textContent = "Couldn't copy the " + what + ", it's in the box instead");
+}
+
+load.addEventListener("click", (event) => {
+    loadText(notation.value);
+});
+
+copy_fen.addEventListener("click", (event) => {
+    copyText(game.fen(), "fen");
+});
+
+copy_pgn.addEventListener("click", (event) => {
+    copyText(game.pgn(), "pgn");
+});
+
+download_pgn.addEventListener("click", (event) => {
+    const url = URL.createObjectURL(new Blob([game.pgn()], {type: "application/x-chess-pgn"}));
+    const link = document.createElement("a");
+    link.href = url;
+    link.download = "game.pgn";
+    link.click();
+    URL.revokeObjectURL(url);
+});
+
+share.addEventListener("click", (event) => {
+    const url = location.origin + location.pathname + "#pgn=" + encodeURIComponent(game.pgn());
+    copyText(url, "link");
+});
+
+window.addEventListener("hashchange", (event) => {
+    loadFragment();
+});
+
+loadFragment();
 
 const render_loop = () => {
-    setTimeout(() => {
-        if (playing) {
-            var done = game.loop_step();
-            if (done) {
-                playing = false;
-            }
-        } else {
-            game.render();
-        }
-        animation_id = requestAnimationFrame(render_loop);
-    }, 1000/fps)
+    const request = game.tick();
+    if (request !== undefined) {
+        worker.postMessage({type: "think", request: request});
+    }
+    play_stop.textContent = game.playing() ? "Pause" : "Play";
+    game.render();
+    requestAnimationFrame(render_loop);
 }
 
 render_loop();
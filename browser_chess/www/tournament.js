@@ -0,0 +1,116 @@
+import {setup_tournament} from "browser_chess";
+
+const canvas = document.getElementById('canvas');
+const status = document.getElementById('status');
+const players = document.getElementById('players');
+const standings = document.getElementById('standings');
+const cross_table = document.getElementById('cross_table');
+const games = document.getElementById('games');
+const moves = document.getElementById('moves');
+const rounds = document.getElementById('rounds');
+const seed = document.getElementById('seed');
+const start = document.getElementById('start');
+const stop = document.getElementById('stop');
+const definitions = document.getElementById('definitions');
+const back = document.getElementById('back');
+const forward = document.getElementById('forward');
+
+var page = setup_tournament(canvas, status, players, standings, cross_table, games, moves);
+
+// the games are played in a worker, a fresh one needs to be told about any
+// strategy files again
+var worker = null;
+var loaded_definitions = [];
+
+function startWorker() {
+    worker = new Worker(new URL('./tournament_worker.js', import.meta.url));
+    worker.onmessage = handleMessage;
+    for (const text of loaded_definitions) {
+        worker.postMessage({type: "definitions", text: text});
+    }
+}
+
+function handleMessage(event) {
+    const message = event.data;
+    if (message.type === "started") {
+        page.start(message.players, message.total);
+    } else if (message.type === "game") {
+        page.add_game(message.game);
+    } else if (message.type === "error") {
+        page.show_error(message.message);
+    }
+}
+
+startWorker();
+
+start.addEventListener("click", (event) => {
+    // anything still going from last time is dropped
+    worker.terminate();
+    startWorker();
+    worker.postMessage({
+        type: "start",
+        players: page.selected(),
+        rounds: Number(rounds.value),
+        seed: Number(seed.value),
+    });
+});
+
+stop.addEventListener("click", (event) => {
+    worker.terminate();
+    startWorker();
+    page.show_error("Stopped");
+});
+
+games.addEventListener("click", (event) => {
+    const game = event.target.dataset.game;
+    if (game !== undefined) {
+        page.show_game(Number(game));
+    }
+});
+
+moves.addEventListener("click", (event) => {
+    const ply = event.target.dataset.ply;
+    if (ply !== undefined) {
+        page.go_to(Number(ply));
+    }
+});
+
+back.addEventListener("click", (event) => {
+    page.back();
+});
+
+forward.addEventListener("click", (event) => {
+    page.forward();
+});
+
+document.addEventListener("keydown", (event) => {
+    if (event.target.tagName === "INPUT") {
+        return;
+    }
+    if (event.key === "ArrowLeft") {
+        page.back();
+    } else if (event.key === "ArrowRight") {
+        page.forward();
+    }
+});
+
+definitions.addEventListener("change", async (event) => {
+    for (const file of definitions.files) {
+        try {
+            const text = await file.text();
+            page.load_definitions(text);
+            loaded_definitions.push(text);
+            worker.postMessage({type: "definitions", text: text});
+        } catch (e) {
+            page.show_error("Could not load " + file.name + ": " + e);
+        }
+    }
+});
+
+// the pieces load in the background, so keep drawing until they show up
+const render_loop = () => {
+    page.render();
+    requestAnimationFrame(render_loop);
+}
+
+render_loop();
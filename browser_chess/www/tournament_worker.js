@@ -0,0 +1,33 @@
+// plays the tournament's games so the page stays usable while they go
+// see tournament.rs for the Referee
+const ready = import("browser_chess").then((wasm) => new wasm.Referee());
+
+onmessage = async (event) => {
+    const referee = await ready;
+    const message = event.data;
+    if (message.type === "definitions") {
+        try {
+            referee.load_definitions(message.text);
+        } catch (e) {
+            // the page loads them too and already said what went wrong
+        }
+    } else if (message.type === "start") {
+        let total;
+        try {
+            total = referee.start(message.players, message.rounds, message.seed);
+        } catch (e) {
+            postMessage({type: "error", message: "Could not start: " + e});
+            return;
+        }
+        postMessage({type: "started", players: message.players, total: total});
+        // stopping is the page getting rid of the whole worker
+        let game;
+        try {
+            while ((game = referee.play_next()) !== undefined) {
+                postMessage({type: "game", game: game});
+            }
+        } catch (e) {
+            postMessage({type: "error", message: "Could not play the next game: " + e});
+        }
+    }
+};
@@ -3,14 +3,23 @@ const HtmlWebpackPlugin = require('html-webpack-plugin');
 const WasmPackPlugin = require("@wasm-tool/wasm-pack-plugin");
 
 module.exports = {
-    entry: './index.js',
+    entry: {
+        index: './index.js',
+        tournament: './tournament.js',
+    },
     output: {
         path: path.resolve(__dirname, 'dist'),
-        filename: 'index.js',
+        filename: '[name].js',
     },
     plugins: [
         new HtmlWebpackPlugin({
-            template: 'index.html'
+            template: 'index.html',
+            chunks: ['index'],
+        }),
+        new HtmlWebpackPlugin({
+            template: 'tournament.html',
+            filename: 'tournament.html',
+            chunks: ['tournament'],
         }),
         new WasmPackPlugin({
             crateDirectory: path.resolve(__dirname, ".")
@@ -0,0 +1,29 @@
+// the bots think in here so a slow one doesn't freeze the page
+// see thinker.rs for what the messages mean
+const ready = import("browser_chess").then((wasm) => new wasm.Thinker());
+
+onmessage = async (event) => {
+    const thinker = await ready;
+    const message = event.data;
+    if (message.type === "definitions") {
+        try {
+            thinker.load_definitions(message.text);
+        } catch (e) {
+            // the page loads them too and already said what went wrong
+        }
+    } else if (message.type === "think") {
+        try {
+            postMessage(thinker.answer(message.request));
+        } catch (e) {
+            // still answer, otherwise the page waits on this forever
+            const request = JSON.parse(message.request);
+            postMessage(JSON.stringify({
+                id: request.id,
+                generation: request.generation,
+                ply: request.ply,
+                action: null,
+                error: String(e),
+            }));
+        }
+    }
+};
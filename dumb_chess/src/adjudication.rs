@@ -0,0 +1,153 @@
+use serde::{Deserialize, Serialize};
+
+use crate::definition::{Evaluator, Side};
+use crate::final_state::{FinalState::*, GameResult, Reason};
+use crate::game::ChessGame;
+use crate::player::Player::{self, *};
+use crate::strategy::EvalGame;
+
+// calling games early so matches between bots that will never finish do
+// eventually finish
+
+pub trait Adjudicate {
+    // the result if the game should stop here, None to keep playing
+    fn adjudicate(&mut self, game: &ChessGame) -> Option<GameResult>;
+
+    // forget anything kept track of in the last game
+    fn new_game(&mut self) {}
+}
+
+// never steps in
+impl Adjudicate for () {
+    fn adjudicate(&mut self, _game: &ChessGame) -> Option<GameResult> {
+        None
+    }
+}
+
+impl<F: FnMut(&ChessGame) -> Option<GameResult>> Adjudicate for F {
+    fn adjudicate(&mut self, game: &ChessGame) -> Option<GameResult> {
+        self(game)
+    }
+}
+
+// the first one to have an opinion wins
+impl Adjudicate for Vec<Box<dyn Adjudicate>> {
+    fn adjudicate(&mut self, game: &ChessGame) -> Option<GameResult> {
+        self.iter_mut().find_map(|a| a.adjudicate(game))
+    }
+
+    fn new_game(&mut self) {
+        for a in self.iter_mut() {
+            a.new_game();
+        }
+    }
+}
+
+// call it a draw once the game has gone on long enough and the reference
+// evaluator has thought it was about even for a while
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DrawRule {
+    // don't start counting until this many plies have been played
+    pub after: u32,
+    // how many plies in a row it has to look even
+    pub plies: u32,
+    // how close to 0 counts as even
+    pub margin: f32,
+}
+
+// give the game to whoever the reference evaluator has thought was well ahead
+// for a while
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ResignRule {
+    // how many plies in a row the same player has to be ahead
+    pub plies: u32,
+    // how far ahead counts as decided
+    pub margin: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rules {
+    pub max_plies: Option<u32>,
+    pub draw: Option<DrawRule>,
+    pub resign: Option<ResignRule>,
+    // looked at from white's side, positive is good for white
+    pub evaluator: Evaluator,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            max_plies: None,
+            draw: None,
+            resign: None,
+            evaluator: Evaluator::Material(Side::Me),
+        }
+    }
+}
+
+pub struct Adjudicator {
+    rules: Rules,
+    evaluator: Box<dyn EvalGame>,
+    // plies in a row that looked even
+    even: u32,
+    // who has been ahead and for how many plies in a row
+    ahead: Option<(Player, u32)>,
+}
+
+impl Adjudicator {
+    pub fn new(rules: Rules) -> Self {
+        let evaluator = rules.evaluator.build(White);
+        Adjudicator { rules, evaluator, even: 0, ahead: None }
+    }
+
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+}
+
+impl Adjudicate for Adjudicator {
+    fn adjudicate(&mut self, game: &ChessGame) -> Option<GameResult> {
+        let plies = game.moves.len() as u32;
+        if self.rules.max_plies.is_some_and(|max| plies >= max) {
+            return Some(GameResult::new(Draw, Reason::MaxPlies));
+        }
+        // evaluators look at the move that got to the position, so there is
+        // nothing to say before the first one
+        let last = *game.moves.last()?;
+        let value = self.evaluator.eval(last, game);
+
+        if let Some(draw) = self.rules.draw {
+            if plies >= draw.after && value.abs() <= draw.margin {
+                self.even += 1;
+            } else {
+                self.even = 0;
+            }
+            if self.even >= draw.plies {
+                return Some(GameResult::new(Draw, Reason::QuietDraw));
+            }
+        }
+
+        if let Some(resign) = self.rules.resign {
+            if value.abs() >= resign.margin {
+                let leader = if value > 0.0 { White } else { Black };
+                self.ahead = match self.ahead {
+                    Some((p, n)) if p == leader => Some((p, n + 1)),
+                    _ => Some((leader, 1)),
+                };
+            } else {
+                self.ahead = None;
+            }
+            if let Some((leader, n)) = self.ahead {
+                if n >= resign.plies {
+                    return Some(GameResult::new(Win(leader), Reason::Resignation));
+                }
+            }
+        }
+        None
+    }
+
+    fn new_game(&mut self) {
+        self.even = 0;
+        self.ahead = None;
+    }
+}
@@ -0,0 +1,61 @@
+use crate::action::Action;
+use crate::definition::{Evaluator, Side::*};
+use crate::game::ChessGame;
+use crate::notation::san;
+use crate::registry::Registry;
+
+// looking inside a position to see why the bots do what they do
+
+// every built in evaluator from the point of view of the player to move,
+// with a short name for each
+pub fn evaluators() -> Vec<(&'static str, Evaluator)> {
+    vec![
+        ("KingDist", Evaluator::KingDistance(Me, Opponent)),
+        ("BigPlays", Evaluator::BigPlays),
+        ("Attacks", Evaluator::Attacks),
+        ("CountMoves", Evaluator::CountMoves),
+        ("MyMoves", Evaluator::MoveAmount(Me)),
+        ("TheirMoves", Evaluator::MoveAmount(Opponent)),
+        ("KingMoves", Evaluator::KingMoves(Me)),
+        ("QueenMoves", Evaluator::QueenMoves),
+        ("InCheck", Evaluator::InCheck(Me)),
+        ("Checking", Evaluator::InCheck(Opponent)),
+        ("Material", Evaluator::Material(Me)),
+    ]
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveScores {
+    pub action: Action,
+    pub san: String,
+    // in the same order as evaluators()
+    pub scores: Vec<f32>,
+}
+
+// each legal move scored the way a depth 1 strategy would see it
+pub fn score_moves(game: &ChessGame) -> Vec<MoveScores> {
+    let evals: Vec<_> = evaluators().iter().map(|(_, e)| e.build(game.turn)).collect();
+    let mut moves: Vec<MoveScores> = game.possible_moves(game.turn)
+        .map(|act| {
+            let after = game.step(act);
+            MoveScores {
+                action: act,
+                san: san(game, act),
+                scores: evals.iter().map(|e| e.eval(act, &after)).collect(),
+            }
+        })
+        .collect();
+    moves.sort_by(|a, b| a.san.cmp(&b.san));
+    moves
+}
+
+// what every strategy in the registry would play, fresh out of the box
+pub fn choices(game: &ChessGame, registry: &Registry) -> Vec<(String, Option<Action>)> {
+    registry.entries()
+        .map(|entry| {
+            let mut bot = entry.create();
+            bot.new_game();
+            (entry.name().to_string(), bot.choose(game))
+        })
+        .collect()
+}
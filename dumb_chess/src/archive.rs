@@ -0,0 +1,223 @@
+use std::fmt::Display;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::action::Action;
+use crate::batch::GameRecord;
+use crate::final_state::{FinalState::*, GameResult, Reason};
+use crate::game::ChessGame;
+use crate::notation::{parse_uci, uci, write_pgn};
+use crate::player::Player::{self, *};
+
+// finished games kept in a file, one json object per line
+// lines are only ever added so the file can be appended to while something
+// else is reading it, and a batch that dies halfway keeps the games it played
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchivedGame {
+    pub white: String,
+    pub black: String,
+    pub seed: u64,
+    // uci, from the usual starting position
+    pub moves: Vec<String>,
+    pub result: GameResult,
+    pub plies: usize,
+    pub duration_ms: u64,
+    // seconds since the unix epoch
+    pub played_at: u64,
+}
+
+#[derive(Debug)]
+pub enum ArchiveError {
+    Io(std::io::Error),
+    // the line number (from 1) and what was wrong with it
+    Json(usize, serde_json::Error),
+}
+
+impl Display for ArchiveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArchiveError::Io(e) => write!(f, "could not use archive: {}", e),
+            ArchiveError::Json(line, e) => write!(f, "bad game on line {}: {}", line, e),
+        }
+    }
+}
+
+impl std::error::Error for ArchiveError {}
+
+impl ArchivedGame {
+    pub fn new(white: &str, black: &str, seed: u64, moves: &[Action], result: GameResult, duration: Duration) -> Self {
+        ArchivedGame {
+            white: white.to_string(),
+            black: black.to_string(),
+            seed,
+            moves: moves.iter().map(|m| uci(*m)).collect(),
+            result,
+            plies: moves.len(),
+            duration_ms: duration.as_millis() as u64,
+            played_at: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()),
+        }
+    }
+
+    // the moves played again, None if any of them don't make sense
+    pub fn actions(&self) -> Option<Vec<Action>> {
+        let mut game = ChessGame::new();
+        let mut actions = Vec::new();
+        for m in &self.moves {
+            let act = parse_uci(&game, m)?;
+            game = game.step(act);
+            actions.push(act);
+        }
+        Some(actions)
+    }
+
+    // back to how a batch keeps it, None if the moves don't make sense
+    pub fn record(&self) -> Option<GameRecord> {
+        Some(GameRecord {
+            white: self.white.clone(),
+            black: self.black.clone(),
+            seed: self.seed,
+            result: self.result,
+            moves: self.actions()?,
+            duration: Duration::from_millis(self.duration_ms),
+        })
+    }
+
+    pub fn player(&self, color: Player) -> &str {
+        match color {
+            White => &self.white,
+            Black => &self.black,
+        }
+    }
+}
+
+impl From<&GameRecord> for ArchivedGame {
+    fn from(record: &GameRecord) -> Self {
+        ArchivedGame::new(&record.white, &record.black, record.seed, &record.moves, record.result, record.duration)
+    }
+}
+
+pub struct Archive {
+    path: PathBuf,
+}
+
+impl Archive {
+    // the file doesn't have to exist yet
+    pub fn new(path: &Path) -> Self {
+        Archive { path: path.to_path_buf() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn append(&self, game: &ArchivedGame) -> Result<(), ArchiveError> {
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path).map_err(ArchiveError::Io)?;
+        // a whole line in one write so games don't get mixed together
+        let line = serde_json::to_string(game).map_err(|e| ArchiveError::Io(e.into()))? + "\n";
+        file.write_all(line.as_bytes()).map_err(ArchiveError::Io)
+    }
+
+    pub fn games(&self) -> Result<Vec<ArchivedGame>, ArchiveError> {
+        let text = std::fs::read_to_string(&self.path).map_err(ArchiveError::Io)?;
+        text.lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| serde_json::from_str(line).map_err(|e| ArchiveError::Json(i + 1, e)))
+            .collect()
+    }
+}
+
+// how it went for the player being asked about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Win,
+    Draw,
+    Loss,
+}
+
+// picking games out of the archive, anything left as None matches everything
+// e.g. all of Ape's losses as white:
+// Query { player: Some("Ape".to_string()), color: Some(White), outcome: Some(Outcome::Loss), ..Query::default() }
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Query {
+    pub player: Option<String>,
+    // the color the player had, or just whose side the outcome is from
+    // without a player
+    pub color: Option<Player>,
+    pub outcome: Option<Outcome>,
+    pub reason: Option<Reason>,
+}
+
+impl Query {
+    pub fn matches(&self, game: &ArchivedGame) -> bool {
+        if self.reason.is_some_and(|r| r != game.result.reason) {
+            return false;
+        }
+        let colors = match self.color {
+            Some(color) => vec![color],
+            None => vec![White, Black],
+        };
+        colors.into_iter().any(|color| {
+            self.player.as_ref().is_none_or(|p| p == game.player(color))
+                && self.outcome.is_none_or(|o| o == outcome(game.result, color))
+        })
+    }
+}
+
+fn outcome(result: GameResult, color: Player) -> Outcome {
+    match result.state {
+        Win(p) if p == color => Outcome::Win,
+        Win(_) => Outcome::Loss,
+        Draw => Outcome::Draw,
+    }
+}
+
+pub fn result_text(result: GameResult) -> &'static str {
+    match result.state {
+        Win(White) => "1-0",
+        Win(Black) => "0-1",
+        Draw => "1/2-1/2",
+    }
+}
+
+// None when the moves in the archive can't be played
+pub fn to_pgn(game: &ArchivedGame) -> Option<String> {
+    let actions = game.actions()?;
+    let result = result_text(game.result);
+    let (year, month, day) = date(game.played_at);
+    let termination = if game.result.reason.adjudicated() { "adjudication" } else { "normal" };
+
+    let tags = [
+        ("Event", "dumb_chess".to_string()),
+        ("Site", "?".to_string()),
+        ("Date", format!("{:04}.{:02}.{:02}", year, month, day)),
+        ("Round", "-".to_string()),
+        ("White", game.white.clone()),
+        ("Black", game.black.clone()),
+        ("Result", result.to_string()),
+        ("Termination", termination.to_string()),
+        ("Reason", format!("{:?}", game.result.reason)),
+        ("Seed", game.seed.to_string()),
+    ];
+    Some(write_pgn(&tags, &ChessGame::new(), &actions, result))
+}
+
+// days since the epoch to a calendar date
+// http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+fn date(secs: u64) -> (i64, u64, u64) {
+    let z = (secs / 86400) as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097) as u64;
+    let yoe = (doe - doe/1460 + doe/36524 - doe/146096) / 365;
+    let doy = doe - (365*yoe + yoe/4 - yoe/100);
+    let mp = (5*doy + 2) / 153;
+    let day = doy - (153*mp + 2)/5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe as i64 + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
@@ -0,0 +1,241 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use crate::action::Action;
+use crate::adjudication::Adjudicate;
+use crate::bot::Bot;
+use crate::final_state::{FinalState::*, GameResult};
+use crate::game::play_game_quietly;
+use crate::player::Player::*;
+use crate::rng;
+use crate::sprt::elo_to_score;
+
+// playing lots of games spread over a few threads
+// every game gets its own seed, so any single game can be played again with
+// the normal cli and --seed
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    pub white: String,
+    pub black: String,
+    pub seed: u64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameRecord {
+    pub white: String,
+    pub black: String,
+    pub seed: u64,
+    pub result: GameResult,
+    pub moves: Vec<Action>,
+    pub duration: Duration,
+}
+
+#[derive(Debug)]
+pub enum BatchError {
+    UnknownStrategy(String),
+}
+
+impl Display for BatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BatchError::UnknownStrategy(name) => write!(f, "unknown strategy: {}", name),
+        }
+    }
+}
+
+impl std::error::Error for BatchError {}
+
+// every player against every other player with both colors, `rounds` times
+pub fn round_robin(players: &[String], rounds: u32, seed: u64) -> Vec<Game> {
+    let mut games = Vec::new();
+    for _ in 0..rounds {
+        for white in players {
+            for black in players {
+                if white != black {
+                    let seed = rng::game_seed(seed, games.len() as u64);
+                    games.push(Game { white: white.clone(), black: black.clone(), seed });
+                }
+            }
+        }
+    }
+    games
+}
+
+type MakeBot<'a> = dyn Fn(&str) -> Option<Box<dyn Bot>> + Sync + 'a;
+type MakeJudge<'a> = dyn Fn() -> Box<dyn Adjudicate> + Sync + 'a;
+
+pub struct Batch<'a> {
+    pub threads: usize,
+    // bots aren't Send, so each thread makes its own
+    bots: Box<MakeBot<'a>>,
+    judge: Box<MakeJudge<'a>>,
+}
+
+impl<'a> Batch<'a> {
+    pub fn new<B, J>(bots: B, judge: J) -> Self
+    where
+        B: Fn(&str) -> Option<Box<dyn Bot>> + Sync + 'a,
+        J: Fn() -> Box<dyn Adjudicate> + Sync + 'a,
+    {
+        let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+        Batch { threads, bots: Box::new(bots), judge: Box::new(judge) }
+    }
+
+    // progress is told about each game as it finishes (along with how many
+    // have finished), the records come back in the same order as the games
+    pub fn run(&self, games: &[Game], mut progress: impl FnMut(usize, &GameRecord)) -> Result<Vec<GameRecord>, BatchError> {
+        for game in games {
+            for name in [&game.white, &game.black] {
+                if (self.bots)(name).is_none() {
+                    return Err(BatchError::UnknownStrategy(name.clone()));
+                }
+            }
+        }
+
+        let next = AtomicUsize::new(0);
+        let (send, receive) = mpsc::channel();
+        let mut records: Vec<Option<GameRecord>> = vec![None; games.len()];
+        std::thread::scope(|scope| {
+            for _ in 0..self.threads.max(1) {
+                let send = send.clone();
+                let next = &next;
+                scope.spawn(move || loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(game) = games.get(i) else {
+                        break;
+                    };
+                    if send.send((i, self.play(game))).is_err() {
+                        break;
+                    }
+                });
+            }
+            // only the threads hold on to senders now, so this ends when they do
+            drop(send);
+            for (done, (i, record)) in receive.iter().enumerate() {
+                progress(done + 1, &record);
+                records[i] = Some(record);
+            }
+        });
+        Ok(records.into_iter().flatten().collect())
+    }
+
+    fn play(&self, game: &Game) -> GameRecord {
+        let mut white = (self.bots)(&game.white).unwrap();
+        let mut black = (self.bots)(&game.black).unwrap();
+        let mut judge = (self.judge)();
+        rng::seed(game.seed);
+        let start = Instant::now();
+        let (end, result) = play_game_quietly(black.as_mut(), white.as_mut(), judge.as_mut());
+        GameRecord {
+            white: game.white.clone(),
+            black: game.black.clone(),
+            seed: game.seed,
+            result,
+            moves: end.moves,
+            duration: start.elapsed(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Tally {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+impl Tally {
+    pub fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    // a point for a win and half for a draw
+    pub fn score(&self) -> f32 {
+        self.wins as f32 + self.draws as f32 / 2.0
+    }
+
+    fn add(&mut self, won: Option<bool>) {
+        match won {
+            Some(true) => self.wins += 1,
+            Some(false) => self.losses += 1,
+            None => self.draws += 1,
+        }
+    }
+}
+
+// how white did, None for a draw
+fn white_won(record: &GameRecord) -> Option<bool> {
+    match record.result.state {
+        Win(White) => Some(true),
+        Win(Black) => Some(false),
+        Draw => None,
+    }
+}
+
+// every player's totals, best first
+pub fn standings(records: &[GameRecord]) -> Vec<(String, Tally)> {
+    let mut totals: BTreeMap<String, Tally> = BTreeMap::new();
+    for r in records {
+        let won = white_won(r);
+        totals.entry(r.white.clone()).or_default().add(won);
+        totals.entry(r.black.clone()).or_default().add(won.map(|w| !w));
+    }
+    let mut totals: Vec<_> = totals.into_iter().collect();
+    totals.sort_by(|a, b| b.1.score().total_cmp(&a.1.score()));
+    totals
+}
+
+// (white, black) to how white did in those games
+pub fn cross_table(records: &[GameRecord]) -> BTreeMap<(String, String), Tally> {
+    let mut table: BTreeMap<(String, String), Tally> = BTreeMap::new();
+    for r in records {
+        table.entry((r.white.clone(), r.black.clone())).or_default().add(white_won(r));
+    }
+    table
+}
+
+// rough elo for everyone from all of their games, nudging the ratings until
+// they predict the scores that actually happened, centred on 0 and best first
+// someone who won or lost everything would go off forever, so it stops at 1000
+pub fn ratings(records: &[GameRecord]) -> Vec<(String, f64)> {
+    let players: Vec<String> = standings(records).into_iter().map(|(name, _)| name).collect();
+    let index = |name: &str| players.iter().position(|p| p == name).unwrap();
+    let games: Vec<(usize, usize, f64)> = records.iter()
+        .map(|r| {
+            let score = match white_won(r) {
+                Some(true) => 1.0,
+                Some(false) => 0.0,
+                None => 0.5,
+            };
+            (index(&r.white), index(&r.black), score)
+        })
+        .collect();
+
+    let mut ratings = vec![0.0; players.len()];
+    for _ in 0..500 {
+        let mut surprise = vec![0.0; players.len()];
+        let mut played = vec![0.0; players.len()];
+        for &(white, black, score) in &games {
+            let expected = elo_to_score(ratings[white] - ratings[black]);
+            surprise[white] += score - expected;
+            surprise[black] -= score - expected;
+            played[white] += 1.0;
+            played[black] += 1.0;
+        }
+        for i in 0..players.len() {
+            ratings[i] = (ratings[i] + 200.0 * surprise[i] / played[i]).clamp(-1000.0, 1000.0);
+        }
+        let mean = ratings.iter().sum::<f64>() / players.len() as f64;
+        for r in ratings.iter_mut() {
+            *r -= mean;
+        }
+    }
+
+    let mut ratings: Vec<_> = players.into_iter().zip(ratings).collect();
+    ratings.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ratings
+}
@@ -0,0 +1,195 @@
+use std::fmt::Display;
+use std::path::Path;
+use std::sync::Arc;
+
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::seq::SliceRandom;
+
+use crate::action::Action::{self, *};
+use crate::bot::Bot;
+use crate::coord::Coord;
+use crate::definition::Evaluator;
+use crate::game::ChessGame;
+use crate::notation::move_squares;
+use crate::piece::Piece::{self, *};
+use crate::rng::with_rng;
+use crate::trace::Trace;
+use crate::zobrist::polyglot_key;
+
+// polyglot opening books
+// a book is a list of 16 byte entries sorted by the position's key:
+//   key: u64, move: u16, weight: u16, learn: u32
+// all big endian
+// the key is the standard polyglot zobrist hash, see zobrist.rs
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BookEntry {
+    pub key: u64,
+    pub raw_move: u16,
+    pub weight: u16,
+    pub learn: u32,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Book {
+    entries: Vec<BookEntry>,
+}
+
+#[derive(Debug)]
+pub enum BookError {
+    Io(std::io::Error),
+    // the file isn't a whole number of entries long
+    Truncated(usize),
+}
+
+impl Display for BookError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BookError::Io(e) => write!(f, "could not read book: {}", e),
+            BookError::Truncated(len) => write!(f, "book is {} bytes, which isn't a multiple of 16", len),
+        }
+    }
+}
+
+impl std::error::Error for BookError {}
+
+const ENTRY_SIZE: usize = 16;
+
+impl Book {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BookError> {
+        if !bytes.len().is_multiple_of(ENTRY_SIZE) {
+            return Err(BookError::Truncated(bytes.len()));
+        }
+        let mut entries: Vec<BookEntry> = bytes.chunks_exact(ENTRY_SIZE)
+            .map(|chunk| BookEntry {
+                key: u64::from_be_bytes(chunk[0..8].try_into().unwrap()),
+                raw_move: u16::from_be_bytes(chunk[8..10].try_into().unwrap()),
+                weight: u16::from_be_bytes(chunk[10..12].try_into().unwrap()),
+                learn: u32::from_be_bytes(chunk[12..16].try_into().unwrap()),
+            })
+            .collect();
+        // they should already be sorted, but lookups depend on it
+        entries.sort_by_key(|e| e.key);
+        Ok(Book { entries })
+    }
+
+    pub fn open(path: &Path) -> Result<Self, BookError> {
+        let bytes = std::fs::read(path).map_err(BookError::Io)?;
+        Self::from_bytes(&bytes)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn entries_for(&self, key: u64) -> &[BookEntry] {
+        let start = self.entries.partition_point(|e| e.key < key);
+        let end = self.entries.partition_point(|e| e.key <= key);
+        &self.entries[start..end]
+    }
+
+    // the book moves for the position along with their weights
+    // moves that aren't legal in the game (a bad book, or a key collision) are skipped
+    pub fn moves(&self, game: &ChessGame) -> Vec<(Action, u16)> {
+        self.entries_for(polyglot_key(game)).iter()
+            .filter_map(|e| decode_move(game, e.raw_move).map(|act| (act, e.weight)))
+            .collect()
+    }
+
+    // pick one of the book moves, favoring the heavier ones
+    pub fn choose(&self, game: &ChessGame) -> Option<Action> {
+        let moves = self.moves(game);
+        match WeightedIndex::new(moves.iter().map(|m| m.1)) {
+            Ok(dist) => Some(moves[with_rng(|rng| dist.sample(rng))].0),
+            // every weight is 0, they are all as good as each other
+            Err(_) => with_rng(|rng| moves.choose(rng)).map(|m| m.0),
+        }
+    }
+}
+
+// polyglot rows count up from white's side, castling is written as the king
+// taking its own rook
+pub fn decode_move(game: &ChessGame, raw: u16) -> Option<Action> {
+    let square = |bits: u16| Coord::new(7 - ((bits >> 3) & 7) as i32, (bits & 7) as i32);
+    let to = square(raw);
+    let from = square(raw >> 6);
+    let promotion = (raw >> 12) & 7;
+    game.possible_moves(game.turn).find(|m| match m {
+        ACastle(c) => c.king_from == from && c.rook_from == to,
+        _ => {
+            let (f, t, p) = move_squares(*m);
+            f == from && t == to && promotion_code(p) == promotion
+        }
+    })
+}
+
+fn promotion_code(piece: Option<Piece>) -> u16 {
+    match piece {
+        Some(Knight(_, _)) => 1,
+        Some(Bishop(_, _)) => 2,
+        Some(Rook(_, _)) => 3,
+        Some(Queen(_, _)) => 4,
+        _ => 0,
+    }
+}
+
+// plays from the book for the first few plies and then lets the bot take over
+// also takes over whenever the book runs out
+pub struct Booked {
+    bot: Box<dyn Bot>,
+    book: Arc<Book>,
+    plies: u32,
+}
+
+impl Booked {
+    pub fn new(bot: Box<dyn Bot>, book: Arc<Book>, plies: u32) -> Self {
+        Booked { bot, book, plies }
+    }
+}
+
+impl Bot for Booked {
+    fn name(&self) -> &str {
+        self.bot.name()
+    }
+
+    fn description(&self) -> &str {
+        self.bot.description()
+    }
+
+    fn choose(&mut self, game: &ChessGame) -> Option<Action> {
+        if game.turn_number < self.plies {
+            if let Some(act) = self.book.choose(game) {
+                return Some(act);
+            }
+        }
+        self.bot.choose(game)
+    }
+
+    fn new_game(&mut self) {
+        self.bot.new_game()
+    }
+
+    fn model(&self) -> Option<Box<dyn Bot>> {
+        Some(Box::new(Booked::new(self.bot.model()?, self.book.clone(), self.plies)))
+    }
+
+    fn meet_opponent(&mut self, opponent: &dyn Bot) {
+        self.bot.meet_opponent(opponent)
+    }
+
+    // book moves aren't worked out, so there is nothing to show for them
+    fn trace(&mut self, game: &ChessGame) -> Option<Trace> {
+        if game.turn_number < self.plies && !self.book.moves(game).is_empty() {
+            return None;
+        }
+        self.bot.trace(game)
+    }
+
+    fn evaluator(&self) -> Option<Evaluator> {
+        self.bot.evaluator()
+    }
+}
@@ -0,0 +1,119 @@
+use crate::definition::{Definition, Evaluator};
+use crate::strategy::Strategy;
+use crate::trace::Trace;
+use crate::{game::ChessGame, action::Action};
+
+// anything that can play a game
+// the built in strategies are all Bots, and other crates can write their own
+// and register them so they show up next to the built in ones
+pub trait Bot {
+    fn name(&self) -> &str;
+    fn description(&self) -> &str;
+    // pick the next move for whoever's turn it is, None means giving up
+    fn choose(&mut self, game: &ChessGame) -> Option<Action>;
+    // called before every game so anything remembered from the last one can be dropped
+    fn new_game(&mut self) {}
+    // a copy of this bot that an opponent can use to predict its moves
+    // bots that can't be copied (or would rather not be predicted) give None
+    fn model(&self) -> Option<Box<dyn Bot>> {
+        None
+    }
+    // called before every game, after new_game, with who is on the other side
+    fn meet_opponent(&mut self, _opponent: &dyn Bot) {}
+    // everything that went into choosing a move, for bots that can explain
+    // themselves, this uses up the same randomness as choose
+    fn trace(&mut self, _game: &ChessGame) -> Option<Trace> {
+        None
+    }
+    // what the bot is trying to get more or less of, so its moves can be
+    // explained (see commentary.rs), None when it isn't that simple
+    fn evaluator(&self) -> Option<Evaluator> {
+        None
+    }
+}
+
+impl Bot for Strategy {
+    fn name(&self) -> &str {
+        Strategy::name(self)
+    }
+
+    fn description(&self) -> &str {
+        Strategy::description(self)
+    }
+
+    fn choose(&mut self, game: &ChessGame) -> Option<Action> {
+        self.run(game)
+    }
+
+    fn trace(&mut self, game: &ChessGame) -> Option<Trace> {
+        Strategy::trace(self, game)
+    }
+
+    fn evaluator(&self) -> Option<Evaluator> {
+        Strategy::evaluator(self)
+    }
+
+    fn model(&self) -> Option<Box<dyn Bot>> {
+        Some(Box::new(*self))
+    }
+}
+
+impl Bot for Definition {
+    fn name(&self) -> &str {
+        Definition::name(self)
+    }
+
+    fn description(&self) -> &str {
+        Definition::description(self)
+    }
+
+    fn choose(&mut self, game: &ChessGame) -> Option<Action> {
+        self.run(game)
+    }
+
+    fn trace(&mut self, game: &ChessGame) -> Option<Trace> {
+        Some(Definition::trace(self, game))
+    }
+
+    fn evaluator(&self) -> Option<Evaluator> {
+        Some(self.evaluator.clone())
+    }
+
+    fn model(&self) -> Option<Box<dyn Bot>> {
+        Some(Box::new(self.clone()))
+    }
+}
+
+impl<B: Bot + ?Sized> Bot for Box<B> {
+    fn name(&self) -> &str {
+        self.as_ref().name()
+    }
+
+    fn description(&self) -> &str {
+        self.as_ref().description()
+    }
+
+    fn choose(&mut self, game: &ChessGame) -> Option<Action> {
+        self.as_mut().choose(game)
+    }
+
+    fn new_game(&mut self) {
+        self.as_mut().new_game()
+    }
+
+    fn model(&self) -> Option<Box<dyn Bot>> {
+        self.as_ref().model()
+    }
+
+    fn meet_opponent(&mut self, opponent: &dyn Bot) {
+        self.as_mut().meet_opponent(opponent)
+    }
+
+    fn trace(&mut self, game: &ChessGame) -> Option<Trace> {
+        self.as_mut().trace(game)
+    }
+
+    fn evaluator(&self) -> Option<Evaluator> {
+        self.as_ref().evaluator()
+    }
+}
@@ -0,0 +1,98 @@
+use crate::action::Action;
+use crate::bot::Bot;
+use crate::definition::{Evaluator, Side::{self, *}};
+use crate::game::ChessGame;
+use crate::player::Player::White;
+use crate::strategy::material;
+
+// putting what the bots do into words
+// the bots only keep the move they pick, so the values they cared about are
+// worked out again for the move that was played, from the position before it
+
+// the reference score for a position, material from white's point of view
+// (the same thing adjudication looks at by default)
+pub fn evaluation(game: &ChessGame) -> f32 {
+    material(game, White)
+}
+
+// "Swarm: reduced total distance to the enemy king from 34 to 31", None for
+// bots that can't say what they're after
+pub fn comment(bot: &dyn Bot, game: &ChessGame, act: Action) -> Option<String> {
+    let evaluator = bot.evaluator()?;
+    Some(format!("{}: {}", bot.name(), explain(&evaluator, game, act)))
+}
+
+// what the move did for the evaluator of whoever made it
+pub fn explain(evaluator: &Evaluator, game: &ChessGame, act: Action) -> String {
+    parts(evaluator, game, act).join(", ")
+}
+
+fn parts(evaluator: &Evaluator, game: &ChessGame, act: Action) -> Vec<String> {
+    let player = game.turn;
+    let after = game.step(act);
+    let value = |e: &Evaluator, g: &ChessGame| e.build(player).eval(act, g);
+    let now = value(evaluator, &after);
+    let part = match evaluator {
+        // these look at the position, so there is a before and after
+        Evaluator::KingDistance(pieces, king) => {
+            changed(&distance_name(*pieces, *king), value(evaluator, game), now)
+        }
+        Evaluator::MoveAmount(Me) => changed("moves available", value(evaluator, game), now),
+        Evaluator::MoveAmount(Opponent) => changed("the opponent's moves available", value(evaluator, game), now),
+        Evaluator::Material(Me) => changed("material lead", value(evaluator, game), now),
+        Evaluator::Material(Opponent) => changed("the opponent's material lead", value(evaluator, game), now),
+        Evaluator::InCheck(Opponent) if now > 0.0 => "gave check".to_string(),
+        Evaluator::InCheck(Opponent) => "didn't give check".to_string(),
+        Evaluator::InCheck(Me) => "stayed out of check".to_string(),
+        // and these look at the move itself
+        Evaluator::BigPlays => format!("moved {} squares", now),
+        Evaluator::Attacks if now > 0.0 => "took a piece".to_string(),
+        Evaluator::Attacks => "didn't take anything".to_string(),
+        Evaluator::CountMoves => format!("moved a piece that had moved {} times before", now),
+        Evaluator::KingMoves(Me) if now > 0.0 => "moved the king".to_string(),
+        Evaluator::KingMoves(Me) => "left the king alone".to_string(),
+        // only the opponent's king moving counts, which never happens on this move
+        Evaluator::KingMoves(Opponent) => "left the opponent's king free to move".to_string(),
+        Evaluator::QueenMoves if now > 0.0 => "moved the queen".to_string(),
+        Evaluator::QueenMoves => "left the queen alone".to_string(),
+        // the same things, just wanted more or less of
+        Evaluator::Negate(e) => return parts(e, game, act),
+        Evaluator::Threshold { evaluator, .. } => return parts(evaluator, game, act),
+        Evaluator::Weighted(es) => return es.iter().flat_map(|(_, e)| parts(e, game, act)).collect(),
+    };
+    vec![part]
+}
+
+fn distance_name(pieces: Side, king: Side) -> String {
+    match (pieces, king) {
+        (Me, Opponent) => "total distance to the enemy king",
+        (Me, Me) => "total distance to their own king",
+        (Opponent, Me) => "the enemy's total distance to this side's king",
+        (Opponent, Opponent) => "the enemy's total distance to their own king",
+    }.to_string()
+}
+
+fn changed(what: &str, before: f32, after: f32) -> String {
+    if after < before {
+        format!("reduced {} from {} to {}", what, before, after)
+    } else if after > before {
+        format!("increased {} from {} to {}", what, before, after)
+    } else {
+        format!("kept {} at {}", what, after)
+    }
+}
+
+// how the evaluation reads, + is good for white
+pub fn evaluation_text(score: f32) -> String {
+    if score > 0.0 {
+        format!("+{}", score)
+    } else {
+        format!("{}", score)
+    }
+}
+
+// how much of a bar white gets for a score, a half when even and most of it
+// when a queen or so ahead
+pub fn white_share(score: f32) -> f32 {
+    0.5 + 0.5 * (score / 10.0).tanh()
+}
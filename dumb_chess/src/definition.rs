@@ -0,0 +1,337 @@
+use std::fmt::Display;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::player::Player;
+use crate::strategy::{self, EvalGame, ChooseMove, CombineValues, Stage};
+use crate::trace::Trace;
+use crate::{game::ChessGame, action::Action};
+
+// strategies that are described by data rather than code
+// they are the same pieces that the built in strategies are assembled from,
+// just picked from a file so they can be made up without touching rust
+//
+// a file looks like (toml):
+//
+// [[strategy]]
+// name = "Stalker"
+// description = "Chase the enemy king with everything."
+// evaluator = { KingDistance = ["self", "opponent"] }
+// chooser = "Min"
+// combiner = "Min"
+// depth = 1
+//
+// or the equivalent json with a top level "strategy" list
+//
+// adding `exploit = { samples = 3 }` makes the lookahead ask the opponent (3
+// times) what they would do in reply to each move
+//
+// evaluators can be combined, e.g. to avoid check above all else and then swarm
+// the king there is
+//
+// evaluator = { Weighted = [[-100.0, { InCheck = "self" }], [-1.0, { KingDistance = ["self", "opponent"] }]] }
+//
+// or to only fall back to swarming when moves are equally safe
+//
+// evaluator = { InCheck = "self" }
+// chooser = "Min"
+// combiner = "Min"
+// depth = 2
+// [[strategy.tie_break]]
+// evaluator = { KingDistance = ["self", "opponent"] }
+// chooser = "Min"
+// combiner = "Min"
+
+// who a parameter of an evaluator refers to, relative to the player making
+// the move since definitions don't know which color they are playing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Side {
+    #[serde(rename = "self")]
+    Me,
+    #[serde(rename = "opponent")]
+    Opponent,
+}
+
+impl Side {
+    pub fn resolve(&self, player: Player) -> Player {
+        match self {
+            Side::Me => player,
+            Side::Opponent => player.toggle(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Evaluator {
+    KingDistance(Side, Side),
+    BigPlays,
+    Attacks,
+    CountMoves,
+    MoveAmount(Side),
+    KingMoves(Side),
+    QueenMoves,
+    InCheck(Side),
+    Material(Side),
+    Weighted(Vec<(f32, Evaluator)>),
+    Negate(Box<Evaluator>),
+    Threshold { evaluator: Box<Evaluator>, at: f32 },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Chooser {
+    Max,
+    Min,
+    First,
+    Last,
+    Softmax(f32),
+    Softmin(f32),
+    EpsilonGreedy { epsilon: f32, chooser: Box<Chooser> },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Combiner {
+    Max,
+    Min,
+    Average,
+    Median,
+    Variance,
+    Sum,
+    Product,
+    Constant(f32),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Definition {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub evaluator: Evaluator,
+    pub chooser: Chooser,
+    pub combiner: Combiner,
+    #[serde(default = "default_depth")]
+    pub depth: u8,
+    // later stages that only look at the moves tied for best so far
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tie_break: Vec<TieBreak>,
+    // look ahead through the opponent's predicted replies instead of ignoring them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exploit: Option<ExploitOptions>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ExploitOptions {
+    // how many times to ask the opponent what they would do
+    #[serde(default = "default_samples")]
+    pub samples: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TieBreak {
+    pub evaluator: Evaluator,
+    pub chooser: Chooser,
+    pub combiner: Combiner,
+    #[serde(default = "default_depth")]
+    pub depth: u8,
+}
+
+fn default_depth() -> u8 {
+    1
+}
+
+fn default_samples() -> usize {
+    1
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct DefinitionFile {
+    #[serde(default)]
+    pub strategy: Vec<Definition>,
+}
+
+#[derive(Debug)]
+pub enum DefinitionError {
+    Io(std::io::Error),
+    Toml(toml::de::Error),
+    Json(serde_json::Error),
+    Invalid(String),
+}
+
+impl Display for DefinitionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DefinitionError::Io(e) => write!(f, "could not read strategy file: {}", e),
+            DefinitionError::Toml(e) => write!(f, "invalid toml: {}", e),
+            DefinitionError::Json(e) => write!(f, "invalid json: {}", e),
+            DefinitionError::Invalid(s) => write!(f, "invalid strategy: {}", s),
+        }
+    }
+}
+
+impl std::error::Error for DefinitionError {}
+
+impl Evaluator {
+    // the evaluators are written in terms of actual players so they need to
+    // know who is moving before they can be used
+    pub fn build(&self, player: Player) -> Box<dyn EvalGame> {
+        match self {
+            Evaluator::KingDistance(a, b) => Box::new(strategy::KingDistance(a.resolve(player), b.resolve(player))),
+            Evaluator::BigPlays           => Box::new(strategy::BigPlays),
+            Evaluator::Attacks            => Box::new(strategy::Attacks),
+            Evaluator::CountMoves         => Box::new(strategy::CountMoves),
+            Evaluator::MoveAmount(a)      => Box::new(strategy::MoveAmount(a.resolve(player))),
+            Evaluator::KingMoves(a)       => Box::new(strategy::KingMoves(a.resolve(player))),
+            Evaluator::QueenMoves         => Box::new(strategy::QueenMoves),
+            Evaluator::InCheck(a)         => Box::new(strategy::InCheck(a.resolve(player))),
+            Evaluator::Material(a)        => Box::new(strategy::Material(a.resolve(player))),
+            Evaluator::Weighted(es)       => Box::new(strategy::Weighted(es.iter().map(|(w, e)| (*w, e.build(player))).collect())),
+            Evaluator::Negate(e)          => Box::new(strategy::Negate(e.build(player))),
+            Evaluator::Threshold { evaluator, at } => Box::new(strategy::Threshold(evaluator.build(player), *at)),
+        }
+    }
+}
+
+impl ChooseMove for Chooser {
+    fn choose_move(&self, left: (f32, Action), right: (f32, Action)) -> (f32, Action) {
+        match self {
+            Chooser::Max   => strategy::MaxChoose.choose_move(left, right),
+            Chooser::Min   => strategy::MinChoose.choose_move(left, right),
+            Chooser::First => strategy::FirstChoose.choose_move(left, right),
+            Chooser::Last  => strategy::LastChoose.choose_move(left, right),
+            _ => self.choose(vec![left, right]).unwrap(),
+        }
+    }
+
+    fn choose(&self, candidates: Vec<(f32, Action)>) -> Option<(f32, Action)> {
+        match self {
+            Chooser::Softmax(t) => strategy::SoftmaxChoose(*t).choose(candidates),
+            Chooser::Softmin(t) => strategy::SoftminChoose(*t).choose(candidates),
+            Chooser::EpsilonGreedy { epsilon, chooser } => strategy::EpsilonGreedy(*epsilon, chooser.as_ref()).choose(candidates),
+            _ => candidates.into_iter().reduce(|left, right| self.choose_move(left, right)),
+        }
+    }
+}
+
+impl CombineValues for Combiner {
+    fn combine_values(&self, values: Vec<f32>) -> f32 {
+        match self {
+            Combiner::Max         => strategy::MaxCombine.combine_values(values),
+            Combiner::Min         => strategy::MinCombine.combine_values(values),
+            Combiner::Average     => strategy::AverageCombine.combine_values(values),
+            Combiner::Median      => strategy::MedianCombine.combine_values(values),
+            Combiner::Variance    => strategy::VarianceCombine.combine_values(values),
+            Combiner::Sum         => strategy::SumCombine.combine_values(values),
+            Combiner::Product     => strategy::ProductCombine.combine_values(values),
+            Combiner::Constant(c) => strategy::ConstantCombine(*c).combine_values(values),
+        }
+    }
+}
+
+impl Chooser {
+    // what's wrong with the numbers, if anything
+    fn problem(&self) -> Option<&'static str> {
+        match self {
+            Chooser::Softmax(t) | Chooser::Softmin(t) if *t <= 0.0 || t.is_nan() => Some("needs a temperature above 0"),
+            Chooser::EpsilonGreedy { epsilon, .. } if !(0.0..=1.0).contains(epsilon) => Some("needs an epsilon between 0 and 1"),
+            Chooser::EpsilonGreedy { chooser, .. } => chooser.problem(),
+            _ => None,
+        }
+    }
+}
+
+impl Definition {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn run(&self, game: &ChessGame) -> Option<Action> {
+        let player = game.turn;
+        self.with_stages(player, |stages| match stages {
+            [s] => strategy::strategy(s.depth, player, game, s.eval, s.choose, s.combine),
+            _ => strategy::strategy_lexicographic(player, game, stages),
+        })
+    }
+
+    // what went into the choice run would make
+    pub fn trace(&self, game: &ChessGame) -> Trace {
+        let player = game.turn;
+        self.with_stages(player, |stages| match stages {
+            [s] => strategy::strategy_traced(s.depth, player, game, s.eval, s.choose, s.combine),
+            _ => strategy::strategy_lexicographic_traced(player, game, stages),
+        })
+    }
+
+    // the main stage followed by the tie breaks, the evaluators only live as
+    // long as the call so the stages get handed over instead of returned
+    fn with_stages<T>(&self, player: Player, f: impl FnOnce(&[Stage]) -> T) -> T {
+        let eval = self.evaluator.build(player);
+        let evals: Vec<_> = self.tie_break.iter().map(|t| t.evaluator.build(player)).collect();
+        let mut stages = vec![Stage { depth: self.depth, eval: eval.as_ref(), choose: &self.chooser, combine: &self.combiner }];
+        for (t, e) in self.tie_break.iter().zip(&evals) {
+            stages.push(Stage { depth: t.depth, eval: e.as_ref(), choose: &t.chooser, combine: &t.combiner });
+        }
+        f(&stages)
+    }
+
+    pub fn validate(&self) -> Result<(), DefinitionError> {
+        if self.name.trim().is_empty() {
+            return Err(DefinitionError::Invalid("a strategy needs a name".to_string()));
+        }
+        if self.depth == 0 || self.tie_break.iter().any(|t| t.depth == 0) {
+            return Err(DefinitionError::Invalid(format!("{} needs a depth of at least 1", self.name)));
+        }
+        if let Some(problem) = std::iter::once(&self.chooser).chain(self.tie_break.iter().map(|t| &t.chooser)).find_map(Chooser::problem) {
+            return Err(DefinitionError::Invalid(format!("{} {}", self.name, problem)));
+        }
+        if let Some(exploit) = self.exploit {
+            if exploit.samples == 0 {
+                return Err(DefinitionError::Invalid(format!("{} needs at least 1 sample of the opponent", self.name)));
+            }
+            if !self.tie_break.is_empty() {
+                return Err(DefinitionError::Invalid(format!("{} can't use tie breaks while exploiting the opponent", self.name)));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl DefinitionFile {
+    fn validated(self) -> Result<Vec<Definition>, DefinitionError> {
+        for def in &self.strategy {
+            def.validate()?;
+        }
+        Ok(self.strategy)
+    }
+}
+
+pub fn parse_toml(text: &str) -> Result<Vec<Definition>, DefinitionError> {
+    let file: DefinitionFile = toml::from_str(text).map_err(DefinitionError::Toml)?;
+    file.validated()
+}
+
+pub fn parse_json(text: &str) -> Result<Vec<Definition>, DefinitionError> {
+    let file: DefinitionFile = serde_json::from_str(text).map_err(DefinitionError::Json)?;
+    file.validated()
+}
+
+// guess the format from the contents, json has to start with a brace
+pub fn parse(text: &str) -> Result<Vec<Definition>, DefinitionError> {
+    if text.trim_start().starts_with('{') {
+        parse_json(text)
+    } else {
+        parse_toml(text)
+    }
+}
+
+pub fn load(path: &Path) -> Result<Vec<Definition>, DefinitionError> {
+    let text = std::fs::read_to_string(path).map_err(DefinitionError::Io)?;
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("json") => parse_json(&text),
+        Some("toml") => parse_toml(&text),
+        _ => parse(&text),
+    }
+}
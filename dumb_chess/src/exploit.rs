@@ -0,0 +1,134 @@
+use rand::seq::SliceRandom;
+
+use crate::bot::Bot;
+use crate::rng::with_rng;
+use crate::definition::{Definition, Evaluator, Chooser, Combiner, Side, ExploitOptions};
+use crate::player::Player;
+use crate::strategy::{EvalGame, ChooseMove, CombineValues, AverageCombine};
+use crate::{game::ChessGame, action::Action};
+
+// the regular lookahead only considers the player's own moves since the
+// opponent is usually unknown, but in a game between bots the opponent is
+// right there so their replies can be predicted by asking a copy of them
+
+// who to ask and how many times, asking more than once gives a rough
+// distribution for bots that are random
+pub struct Prediction<'a> {
+    pub opponent: &'a dyn Bot,
+    pub samples: usize,
+}
+
+pub fn strategy_exploit(depth: u8, player: Player, game: &ChessGame, eval: &(impl EvalGame + ?Sized), choose: &(impl ChooseMove + ?Sized), combine: &(impl CombineValues + ?Sized), prediction: &Prediction) -> Option<Action> {
+    let mut moves: Vec<Action> = game.possible_moves(player).collect();
+    with_rng(|rng| moves.shuffle(rng));
+    let values = moves.into_iter()
+        .map(|m| (exploit_lookahead(depth-1, player, m, game, eval, combine, prediction), m))
+        .collect();
+    choose.choose(values).map(|choice| choice.1)
+}
+
+// value of making the move and then whatever the opponent is expected to do
+// in response, the predicted replies are averaged since they are samples
+pub fn exploit_lookahead(depth: u8, player: Player, act: Action, game: &ChessGame, eval: &(impl EvalGame + ?Sized), combine: &(impl CombineValues + ?Sized), prediction: &Prediction) -> f32 {
+    let g = game.step(act);
+    let replies = predict(&g, prediction);
+    if replies.is_empty() {
+        // the opponent has nothing to do so the game is over here
+        return eval.eval(act, &g);
+    }
+    let values = replies.into_iter()
+        .map(|reply| {
+            let g = g.step(reply);
+            if depth == 0 {
+                eval.eval(act, &g)
+            } else {
+                let moves: Vec<Action> = g.possible_moves(player).collect();
+                if moves.is_empty() {
+                    eval.no_moves()
+                } else {
+                    let values = moves.into_iter()
+                        .map(|m| exploit_lookahead(depth-1, player, m, &g, eval, combine, prediction))
+                        .collect();
+                    combine.combine_values(values)
+                }
+            }
+        })
+        .collect();
+    AverageCombine.combine_values(values)
+}
+
+// a fresh copy of the opponent for every guess so the guesses don't leak into
+// each other for bots that remember things
+fn predict(game: &ChessGame, prediction: &Prediction) -> Vec<Action> {
+    (0..prediction.samples)
+        .filter_map(|_| prediction.opponent.model().and_then(|mut m| m.choose(game)))
+        .collect()
+}
+
+pub fn card_counter() -> Definition {
+    Definition {
+        name: "Card counter".to_string(),
+        description: "Knows exactly how you play and keeps their options open accordingly.".to_string(),
+        evaluator: Evaluator::Weighted(vec![
+            (-10.0, Evaluator::InCheck(Side::Me)),
+            (1.0, Evaluator::MoveAmount(Side::Me)),
+            (-1.0, Evaluator::MoveAmount(Side::Opponent)),
+        ]),
+        chooser: Chooser::Max,
+        combiner: Combiner::Average,
+        depth: 1,
+        tie_break: Vec::new(),
+        exploit: Some(ExploitOptions { samples: 2 }),
+    }
+}
+
+// plays a definition, but using the opponent's replies for the lookahead
+// the model of the opponent is taken at the start of the game, so it only
+// knows what the opponent knew then
+pub struct Exploiter {
+    definition: Definition,
+    opponent: Option<Box<dyn Bot>>,
+}
+
+impl Exploiter {
+    pub fn new(definition: Definition) -> Self {
+        Exploiter { definition, opponent: None }
+    }
+}
+
+impl Bot for Exploiter {
+    fn name(&self) -> &str {
+        self.definition.name()
+    }
+
+    fn description(&self) -> &str {
+        self.definition.description()
+    }
+
+    fn choose(&mut self, game: &ChessGame) -> Option<Action> {
+        match &self.opponent {
+            Some(opponent) => {
+                let player = game.turn;
+                let samples = self.definition.exploit.map_or(1, |e| e.samples);
+                let eval = self.definition.evaluator.build(player);
+                let prediction = Prediction { opponent: opponent.as_ref(), samples };
+                strategy_exploit(self.definition.depth, player, game, eval.as_ref(), &self.definition.chooser, &self.definition.combiner, &prediction)
+            }
+            // no one to predict so it is just a regular lookahead
+            None => self.definition.run(game),
+        }
+    }
+
+    fn model(&self) -> Option<Box<dyn Bot>> {
+        // without the opponent, otherwise two of these would model each other forever
+        Some(Box::new(Exploiter::new(self.definition.clone())))
+    }
+
+    fn meet_opponent(&mut self, opponent: &dyn Bot) {
+        self.opponent = opponent.model();
+    }
+
+    fn evaluator(&self) -> Option<Evaluator> {
+        Some(self.definition.evaluator.clone())
+    }
+}
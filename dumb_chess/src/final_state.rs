@@ -1,7 +1,46 @@
+use serde::{Deserialize, Serialize};
+
 use crate::player::Player;
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum FinalState {
     Win(Player),
     Draw,
 }
+
+// why the game ended
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Reason {
+    Checkmate,
+    Stalemate,
+    // 50 plies without a take or a pawn moving
+    FiftyMoves,
+    // the bot didn't come up with a move even though there were some
+    NoMove,
+    // everything below was decided by adjudication rather than played out
+    MaxPlies,
+    // the reference evaluator thought it was level for too long
+    QuietDraw,
+    // the reference evaluator thought it was decided for too long
+    Resignation,
+    // the endgame tables know how it ends
+    Tablebase,
+}
+
+impl Reason {
+    pub fn adjudicated(&self) -> bool {
+        matches!(self, Reason::MaxPlies | Reason::QuietDraw | Reason::Resignation | Reason::Tablebase)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameResult {
+    pub state: FinalState,
+    pub reason: Reason,
+}
+
+impl GameResult {
+    pub fn new(state: FinalState, reason: Reason) -> Self {
+        GameResult { state, reason }
+    }
+}
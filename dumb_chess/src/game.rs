@@ -1,7 +1,7 @@
 use std::{fmt::Display, cmp::min};
 use action::{Action, Move, Take, Promote, PromoteTake, Passant, Castle};
 use coord::Coord;
-use final_state::FinalState;
+use final_state::{FinalState, GameResult, Reason};
 use piece::{Piece, PieceData};
 use player::Player;
 
@@ -10,7 +10,7 @@ use Action::*;
 use Piece::*;
 use FinalState::*;
 
-use crate::{action, coord, final_state, piece, player, strategy::Strategy};
+use crate::{action, coord, final_state, piece, player, bot::Bot, adjudication::Adjudicate};
 
 #[derive(Debug, Clone, Copy)]
 pub struct ChessBoard {
@@ -24,6 +24,20 @@ pub struct ChessGame {
     pub board: ChessBoard,
     pub moves: Vec<Action>,
     pub removed: Vec<Piece>,
+    pub before: Before,
+}
+
+// what is known about the moves before a game that was set up part way through
+// (from a fen), since those aren't in moves
+#[derive(Debug, Clone, Default)]
+pub struct Before {
+    // pieces that have moved, which stops castling and pawns moving two squares
+    pub moved: Vec<Piece>,
+    // the last move, only when it was a pawn moving two squares that can be
+    // taken en passant
+    pub last: Option<Action>,
+    // plies since the last take or pawn move
+    pub halfmoves: usize,
 }
 
 fn starting_board() -> ChessBoard {
@@ -61,22 +75,27 @@ impl ChessBoard {
         })
     }
 
+    // pieces() goes over every square, this is only the ones with something on them
+    pub fn piece_count(&self) -> usize {
+        self.pieces().filter(|p| !p.piece.empty()).count()
+    }
+
     pub fn pieces_for<'a>(&'a self, player: Player) -> impl Iterator<Item=PieceData> + 'a {
-        self.pieces().into_iter()
+        self.pieces()
             .filter(move |p| {
-                p.piece.owner().map_or(false, |x| x == player)
+                p.piece.owner() == Some(player)
             })
     }
 }
 
 impl ChessGame {
     pub fn new() -> Self {
-        ChessGame { turn_number: 0, turn: White, board: ChessBoard::default(), moves: Vec::new() , removed: Vec::new() }
+        ChessGame { turn_number: 0, turn: White, board: ChessBoard::default(), moves: Vec::new() , removed: Vec::new(), before: Before::default() }
     }
 
     // for troubleshooting
     pub fn blank() -> Self {
-        ChessGame { turn_number: 0, turn: White, board: ChessBoard { board: [Empty; 64] }, moves: vec![AMove(Move::new(Empty, (0,0).into(), (0,0).into()))], removed: Vec::new() }
+        ChessGame { turn_number: 0, turn: White, board: ChessBoard { board: [Empty; 64] }, moves: vec![AMove(Move::new(Empty, (0,0).into(), (0,0).into()))], removed: Vec::new(), before: Before::default() }
     }
 
     // do the given action
@@ -124,7 +143,25 @@ impl ChessGame {
         game
     }
 
-    fn has_moved(&self, piece: Piece) -> bool {
+    // the move that got to this position, even when it was before the game
+    pub fn last_move(&self) -> Option<Action> {
+        self.moves.last().copied().or(self.before.last)
+    }
+
+    // plies since the last take or pawn move, for the fifty move rule
+    pub fn halfmoves(&self) -> usize {
+        let since = self.moves.iter().rev().take_while(|m| !resets_clock(**m)).count();
+        if since == self.moves.len() {
+            since + self.before.halfmoves
+        } else {
+            since
+        }
+    }
+
+    pub fn has_moved(&self, piece: Piece) -> bool {
+        if self.before.moved.contains(&piece) {
+            return true;
+        }
         for m in &self.moves {
             match m {
                 AMove(mv) => {
@@ -177,7 +214,7 @@ impl ChessGame {
         // this piece is a pawn, the last move was this pawn with a 2 step move
         // there is an attacker's pawn to the left or right
         if matches!(piece.piece, Pawn(_, _)) {
-            if let Some(AMove(m)) = self.moves.last() {
+            if let Some(AMove(m)) = self.last_move() {
                 if m.piece == piece.piece && (m.from.row - m.to.row).abs() == 2 {
                     if coord.col > 0 {
                         let coord_adj = coord + (0, -1).into();
@@ -372,7 +409,7 @@ impl ChessGame {
             let dest = self.board.get(coord_dest);
             if adj.owned_by(owner.toggle()) && dest.empty() {
                 // last move was a pawn that moved to the adjacent square and moved two spaces
-                if let Some(AMove(m)) = self.moves.last() {
+                if let Some(AMove(m)) = self.last_move() {
                     if matches!(m.piece, Pawn(_, _)) && m.to == coord_adj && (m.from.row - m.to.row).abs() == 2 {
                         moves.push(APassant(Passant::new(orig.piece, adj, coord, coord_dest, coord_adj)));
                     }
@@ -386,7 +423,7 @@ impl ChessGame {
             let dest = self.board.get(coord_dest);
             if adj.owned_by(owner.toggle()) && dest.empty() {
                 // last move was a pawn that moved to the adjacent square and moved two spaces
-                if let Some(AMove(m)) = self.moves.last() {
+                if let Some(AMove(m)) = self.last_move() {
                     if matches!(m.piece, Pawn(_, _)) && m.to == coord_adj && (m.from.row - m.to.row).abs() == 2 {
                         moves.push(APassant(Passant::new(orig.piece, adj, coord, coord_dest, coord_adj)));
                     }
@@ -467,7 +504,7 @@ impl ChessGame {
         //   king goes 2 spaces in the direction of the rook
         //   rook ends up on the inner edge of the king
         if !self.has_moved(orig.piece) {
-            let rooks: Vec<_> = self.board.pieces_for(owner).into_iter()
+            let rooks: Vec<_> = self.board.pieces_for(owner)
                 .filter(|x| matches!(x.piece, Rook(_, _)))
                 .filter(|x| !self.has_moved(x.piece)).collect();
             for piece in rooks {
@@ -599,7 +636,7 @@ impl ChessGame {
 
     // can any of the opponents possible moves do a take on the given player's king?
     pub fn in_check(&self, player: Player) -> bool {
-        let kings = self.board.pieces_for(player).into_iter()
+        let kings = self.board.pieces_for(player)
             .filter(|x| matches!(x.piece, King(_, _))).collect::<Vec<_>>();
         match kings.len() {
             1 => self.can_attack(kings[0], player.toggle()),
@@ -623,46 +660,20 @@ impl ChessGame {
     // this is intended to be used after stepping as a check
     
     pub fn check_state(&self) -> Option<FinalState> {
+        self.result().map(|r| r.state)
+    }
+
+    // check_state along with why it ended
+    pub fn result(&self) -> Option<GameResult> {
         let acts: Vec<_> = self.possible_moves(self.turn).collect();
         if acts.is_empty() {
             if self.in_check(self.turn) {
-                return Some(Win(self.turn.toggle()));
+                return Some(GameResult::new(Win(self.turn.toggle()), Reason::Checkmate));
             } else {
-                return Some(Draw);
-            }
-        } else if self.moves.len() >= 50 {
-            // I imagine this could be better
-            let mut uninteresting = true;
-            for act in &self.moves[self.moves.len()-50..] {
-                match act {
-                    ATake(_) => {
-                        uninteresting = false;
-                        break;
-                    }
-                    APromoteTake(_) => {
-                        uninteresting = false;
-                        break;
-                    }
-                    APromote(_) => {
-                        uninteresting = false;
-                        break;
-                    }
-                    APassant(_) => {
-                        uninteresting = false;
-                        break;
-                    }
-                    AMove(m) => {
-                        if matches!(m.piece, Pawn(_, _)) {
-                            uninteresting = false;
-                            break;
-                        }
-                    }
-                    _ => {}
-                }
-            }
-            if uninteresting {
-                return Some(Draw)
+                return Some(GameResult::new(Draw, Reason::Stalemate));
             }
+        } else if self.halfmoves() >= 50 {
+            return Some(GameResult::new(Draw, Reason::FiftyMoves))
         }
         None
     }
@@ -713,25 +724,111 @@ impl Default for ChessGame {
     }
 }
 
-pub fn play_game(black_player: &Strategy, white_player: &Strategy) -> (ChessGame, FinalState) {
-    let mut game = ChessGame::new();
-    let mut white_turn = true;
+// captures and pawn moves reset the fifty move clock
+pub fn resets_clock(action: Action) -> bool {
+    match action {
+        AMove(m) => matches!(m.piece, Pawn(_, _)),
+        _ => !matches!(action, ACastle(_)),
+    }
+}
+
+pub fn play_game(black_player: &mut dyn Bot, white_player: &mut dyn Bot) -> (ChessGame, GameResult) {
+    play_game_adjudicated(black_player, white_player, &mut ())
+}
+
+// same as play_game, but the adjudicator can end the game early
+pub fn play_game_adjudicated(black_player: &mut dyn Bot, white_player: &mut dyn Bot, judge: &mut dyn Adjudicate) -> (ChessGame, GameResult) {
+    run_game(black_player, white_player, judge, true)
+}
+
+// without printing every move, for playing lots of games at once
+pub fn play_game_quietly(black_player: &mut dyn Bot, white_player: &mut dyn Bot, judge: &mut dyn Adjudicate) -> (ChessGame, GameResult) {
+    run_game(black_player, white_player, judge, false)
+}
+
+fn run_game(black_player: &mut dyn Bot, white_player: &mut dyn Bot, judge: &mut dyn Adjudicate, log: bool) -> (ChessGame, GameResult) {
+    let mut game = Match::new(black_player, white_player, judge);
     loop {
-        println!("{}", game);
-        if let Some(state) = game.check_state() {
-            return (game, state);
-        } else if let Some(act) = if white_turn {
-            white_player.run(&game)
-        } else {
-            black_player.run(&game)
-        } {
-            white_turn = !white_turn;
-            println!("{:?} chose: {:?}", game.turn, act);
-            game = game.step(act);
-        } else {
-            println!("Couldn't make a move, but couldn't determine that ahead of time for some reason");
-            return (game, Draw);
+        if log {
+            println!("{}", game.game);
+        }
+        let turn = game.game.turn;
+        match game.play() {
+            Ply::Moved(act) => {
+                if log {
+                    println!("{:?} chose: {:?}", turn, act);
+                }
+            }
+            Ply::Over(result) => {
+                if log && result.reason.adjudicated() {
+                    println!("Adjudicated: {:?}", result.reason);
+                } else if log && result.reason == Reason::NoMove {
+                    println!("Couldn't make a move, but couldn't determine that ahead of time for some reason");
+                }
+                return (game.game, result);
+            }
         }
     }
 }
 
+// what happened when it was someone's turn
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Ply {
+    Moved(Action),
+    Over(GameResult),
+}
+
+// a game played a ply at a time, for anything that wants to do something in
+// between moves (like show them)
+pub struct Match<'a> {
+    pub game: ChessGame,
+    pub result: Option<GameResult>,
+    black_player: &'a mut dyn Bot,
+    white_player: &'a mut dyn Bot,
+    judge: &'a mut dyn Adjudicate,
+}
+
+impl<'a> Match<'a> {
+    pub fn new(black_player: &'a mut dyn Bot, white_player: &'a mut dyn Bot, judge: &'a mut dyn Adjudicate) -> Self {
+        black_player.new_game();
+        white_player.new_game();
+        black_player.meet_opponent(white_player);
+        white_player.meet_opponent(black_player);
+        judge.new_game();
+        Match { game: ChessGame::new(), result: None, black_player, white_player, judge }
+    }
+
+    pub fn player(&self, player: Player) -> &dyn Bot {
+        match player {
+            White => &*self.white_player,
+            Black => &*self.black_player,
+        }
+    }
+
+    // the next move, or how the game ended (which keeps being the answer)
+    pub fn play(&mut self) -> Ply {
+        if let Some(result) = self.result {
+            return Ply::Over(result);
+        }
+        let result = self.game.result().or_else(|| self.judge.adjudicate(&self.game));
+        if let Some(result) = result {
+            self.result = Some(result);
+            return Ply::Over(result);
+        }
+        let choice = match self.game.turn {
+            White => self.white_player.choose(&self.game),
+            Black => self.black_player.choose(&self.game),
+        };
+        match choice {
+            Some(act) => {
+                self.game = self.game.step(act);
+                Ply::Moved(act)
+            }
+            None => {
+                let result = GameResult::new(Draw, Reason::NoMove);
+                self.result = Some(result);
+                Ply::Over(result)
+            }
+        }
+    }
+}
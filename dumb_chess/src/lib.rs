@@ -1,7 +1,28 @@
 pub mod coord;
+pub mod rng;
 pub mod action;
 pub mod piece;
 pub mod game;
 pub mod final_state;
 pub mod strategy;
 pub mod player;
+pub mod definition;
+pub mod bot;
+pub mod registry;
+pub mod stateful;
+pub mod exploit;
+pub mod mcts;
+pub mod notation;
+pub mod book;
+pub mod zobrist;
+pub mod adjudication;
+pub mod batch;
+pub mod archive;
+pub mod sprt;
+pub mod analysis;
+pub mod commentary;
+pub mod trace;
+#[cfg(feature = "syzygy")]
+pub mod tablebase;
+#[cfg(feature = "tui")]
+pub mod tui;
@@ -1,18 +1,210 @@
-use clap::Parser;
-use dumb_chess::game::play_game;
-use dumb_chess::strategy::*;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+
+use clap::{Parser, Subcommand};
+use dumb_chess::{definition, rng};
+use dumb_chess::book::{Book, Booked};
+use dumb_chess::bot::Bot;
+use dumb_chess::analysis;
+use dumb_chess::archive::{self, Archive, ArchivedGame, Outcome, Query};
+use dumb_chess::adjudication::{Adjudicate, Adjudicator, DrawRule, ResignRule, Rules};
+use dumb_chess::batch::{self, Batch};
+use dumb_chess::sprt::{self, Sprt};
+use dumb_chess::game::{ChessGame, play_game_adjudicated};
+use dumb_chess::notation;
+#[cfg(feature = "syzygy")]
+use dumb_chess::tablebase::{Endgame, Tablebase, Tablebased};
+#[cfg(feature = "tui")]
+use dumb_chess::tui;
+use dumb_chess::final_state::Reason;
+use dumb_chess::player::Player;
+use dumb_chess::registry::Registry;
 
 
 
 #[derive(Parser)]
 #[command(author, version, about)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
     #[arg(short, long, default_value="Random")]
     white_player: String,
     #[arg(short, long, default_value="Random")]
     black_player: String,
     #[arg(short, long)]
-    list: bool
+    list: bool,
+    /// toml or json file with extra strategy definitions
+    #[arg(short, long, global = true)]
+    strategies: Option<PathBuf>,
+    /// seed for the random choices, to play the same game again
+    #[arg(long, global = true)]
+    seed: Option<u64>,
+    /// jsonl file to add the finished games to
+    #[arg(long, global = true)]
+    archive: Option<PathBuf>,
+    /// polyglot opening book for both players to open from
+    #[arg(long, global = true)]
+    book: Option<PathBuf>,
+    /// how many plies (moves by either side) to play from the book
+    #[arg(long, default_value_t = 16, global = true)]
+    book_plies: u32,
+    /// call the game a draw after this many plies
+    #[arg(long, global = true)]
+    max_plies: Option<u32>,
+    /// call the game a draw once material has been even for this many plies in a row
+    #[arg(long, global = true)]
+    draw_plies: Option<u32>,
+    /// don't start counting even plies until this many have been played
+    #[arg(long, default_value_t = 40, global = true)]
+    draw_after: u32,
+    /// how far apart material can be and still count as even
+    #[arg(long, default_value_t = 0.0, global = true)]
+    draw_margin: f32,
+    /// give the game to whoever has been well ahead in material for this many plies in a row
+    #[arg(long, global = true)]
+    resign_plies: Option<u32>,
+    /// how far ahead in material counts as decided
+    #[arg(long, default_value_t = 5.0, global = true)]
+    resign_margin: f32,
+    /// directory of syzygy tables, games are adjudicated once they cover the position
+    #[cfg(feature = "syzygy")]
+    #[arg(long, global = true)]
+    syzygy: Option<PathBuf>,
+    /// have white play the endgame from the tables
+    #[cfg(feature = "syzygy")]
+    #[arg(long, value_enum, requires = "syzygy")]
+    white_endgame: Option<EndgameArg>,
+    /// have black play the endgame from the tables
+    #[cfg(feature = "syzygy")]
+    #[arg(long, value_enum, requires = "syzygy")]
+    black_endgame: Option<EndgameArg>,
+    /// watch the game full screen, with controls for pausing, stepping and speed
+    #[cfg(feature = "tui")]
+    #[arg(long)]
+    tui: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// play lots of games between strategies across several threads
+    Batch {
+        /// strategies to play, comma separated, all of them if left out
+        #[arg(short, long, value_delimiter = ',')]
+        players: Vec<String>,
+        /// how many times each pair plays with each color
+        #[arg(short, long, default_value_t = 1)]
+        rounds: u32,
+        /// how many games to play at once, one per core if left out
+        #[arg(short = 'j', long)]
+        threads: Option<usize>,
+    },
+    /// play a candidate against a baseline until it is clear whether the candidate is stronger
+    Sprt {
+        candidate: String,
+        baseline: String,
+        /// elo difference where the candidate counts as no better
+        #[arg(long, default_value_t = 0.0, allow_negative_numbers = true)]
+        elo0: f64,
+        /// elo difference where the candidate counts as better
+        #[arg(long, default_value_t = 10.0, allow_negative_numbers = true)]
+        elo1: f64,
+        /// chance of saying it is better when it isn't
+        #[arg(long, default_value_t = 0.05)]
+        alpha: f64,
+        /// chance of saying it isn't better when it is
+        #[arg(long, default_value_t = 0.05)]
+        beta: f64,
+        /// give up after this many pairs of games
+        #[arg(long, default_value_t = 5000)]
+        max_pairs: u64,
+        /// how many games to play at once, one per core if left out
+        #[arg(short = 'j', long)]
+        threads: Option<usize>,
+    },
+    /// score every legal move with every evaluator and show what each strategy would play
+    Analyze {
+        /// the position, the usual starting one if left out
+        #[arg(long)]
+        fen: Option<String>,
+    },
+    /// show everything a strategy looked at to pick its move in a position
+    Explain {
+        strategy: String,
+        /// the position, the usual starting one if left out
+        #[arg(long)]
+        fen: Option<String>,
+        #[arg(short, long, value_enum, default_value_t = TraceFormat::Text)]
+        format: TraceFormat,
+    },
+    /// look through the games in an archive
+    Games {
+        /// the jsonl archive
+        file: PathBuf,
+        /// only games this strategy played
+        #[arg(short, long)]
+        player: Option<String>,
+        /// only games where the player (or anyone) had this color
+        #[arg(short, long, value_enum)]
+        color: Option<ColorArg>,
+        /// only games the player (or anyone) finished like this
+        #[arg(short, long, value_enum)]
+        outcome: Option<OutcomeArg>,
+        /// only games that ended this way
+        #[arg(short, long, value_parser = parse_reason)]
+        reason: Option<Reason>,
+        /// write the games as pgn to this file, - for the terminal
+        #[arg(long)]
+        pgn: Option<PathBuf>,
+    },
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum TraceFormat {
+    Text,
+    Json,
+    /// graphviz
+    Dot,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum ColorArg {
+    White,
+    Black,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum OutcomeArg {
+    Win,
+    Draw,
+    Loss,
+}
+
+fn parse_reason(text: &str) -> Result<Reason, String> {
+    let reasons = [
+        Reason::Checkmate, Reason::Stalemate, Reason::FiftyMoves, Reason::NoMove,
+        Reason::MaxPlies, Reason::QuietDraw, Reason::Resignation, Reason::Tablebase,
+    ];
+    reasons.into_iter()
+        .find(|r| format!("{:?}", r).eq_ignore_ascii_case(text))
+        .ok_or_else(|| format!("reasons are {:?}", reasons))
+}
+
+#[cfg(feature = "syzygy")]
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum EndgameArg {
+    Best,
+    Worst,
+}
+
+#[cfg(feature = "syzygy")]
+impl From<EndgameArg> for Endgame {
+    fn from(arg: EndgameArg) -> Self {
+        match arg {
+            EndgameArg::Best => Endgame::Best,
+            EndgameArg::Worst => Endgame::Worst,
+        }
+    }
 }
 
 
@@ -51,29 +243,319 @@ fn main() {
     */
     
     let cli = Cli::parse();
+
+    if let Some(Command::Games { file, player, color, outcome, reason, pgn }) = &cli.command {
+        let query = Query {
+            player: player.clone(),
+            color: color.map(|c| match c {
+                ColorArg::White => Player::White,
+                ColorArg::Black => Player::Black,
+            }),
+            outcome: outcome.map(|o| match o {
+                OutcomeArg::Win => Outcome::Win,
+                OutcomeArg::Draw => Outcome::Draw,
+                OutcomeArg::Loss => Outcome::Loss,
+            }),
+            reason: *reason,
+        };
+        show_games(&Archive::new(file), &query, pgn.as_deref());
+        return;
+    }
+
+    let mut registry = Registry::new();
+    if let Some(path) = &cli.strategies {
+        match definition::load(path) {
+            Ok(defs) => registry.register_definitions(defs),
+            Err(e) => {
+                println!("Could not load strategies from {}: {}", path.display(), e);
+                return;
+            }
+        }
+    }
     
+    let book = match &cli.book {
+        Some(path) => match Book::open(path) {
+            Ok(book) => Some(Arc::new(book)),
+            Err(e) => {
+                println!("Could not load book from {}: {}", path.display(), e);
+                return;
+            }
+        },
+        None => None,
+    };
+    let with_book = |bot: Box<dyn Bot>| -> Box<dyn Bot> {
+        match &book {
+            Some(book) => Box::new(Booked::new(bot, book.clone(), cli.book_plies)),
+            None => bot,
+        }
+    };
+
+    let rules = Rules {
+        max_plies: cli.max_plies,
+        draw: cli.draw_plies.map(|plies| DrawRule { after: cli.draw_after, plies, margin: cli.draw_margin }),
+        resign: cli.resign_plies.map(|plies| ResignRule { plies, margin: cli.resign_margin }),
+        ..Rules::default()
+    };
+    #[cfg(feature = "syzygy")]
+    let tables = match &cli.syzygy {
+        Some(path) => match Tablebase::open(path) {
+            Ok(tables) => Some(Arc::new(tables)),
+            Err(e) => {
+                println!("Could not load tablebase from {}: {}", path.display(), e);
+                return;
+            }
+        },
+        None => None,
+    };
+    #[cfg(feature = "syzygy")]
+    let with_tables = |bot: Box<dyn Bot>, endgame: Option<EndgameArg>| -> Box<dyn Bot> {
+        match (&tables, endgame) {
+            (Some(tables), Some(endgame)) => Box::new(Tablebased::new(bot, tables.clone(), endgame.into())),
+            _ => bot,
+        }
+    };
+    let make_judge = || -> Box<dyn Adjudicate> {
+        let judges: Vec<Box<dyn Adjudicate>> = vec![Box::new(Adjudicator::new(rules.clone()))];
+        // the tables go first since they actually know
+        #[cfg(feature = "syzygy")]
+        let judges: Vec<Box<dyn Adjudicate>> = tables.iter()
+            .map(|t| Box::new(t.clone()) as Box<dyn Adjudicate>)
+            .chain(judges)
+            .collect();
+        Box::new(judges)
+    };
+
+    let archive = cli.archive.as_deref().map(Archive::new);
+    let keep = |game: ArchivedGame| {
+        if let Some(archive) = &archive {
+            if let Err(e) = archive.append(&game) {
+                println!("Could not add the game to {}: {}", archive.path().display(), e);
+            }
+        }
+    };
+
+    if let Some(Command::Batch { players, rounds, threads }) = &cli.command {
+        let players = if players.is_empty() {
+            registry.entries().map(|e| e.name().to_string()).collect()
+        } else {
+            players.clone()
+        };
+        let seed = cli.seed.unwrap_or_else(rand::random);
+        println!("Seed: {}", seed);
+        let games = batch::round_robin(&players, *rounds, seed);
+        let mut runner = Batch::new(|name| registry.create(name).map(with_book), make_judge);
+        if let Some(threads) = threads {
+            runner.threads = *threads;
+        }
+        let total = games.len();
+        let records = runner.run(&games, |done, r| {
+            println!("[{}/{}] {} vs {}: {:?} ({:?}) in {} plies, seed {}, {:.2}s",
+                done, total, r.white, r.black, r.result.state, r.result.reason, r.moves.len(), r.seed, r.duration.as_secs_f32());
+            keep(r.into());
+        });
+        match records {
+            Ok(records) => print_results(&players, &records),
+            Err(e) => println!("Could not play the games: {}", e),
+        }
+        return;
+    }
+
+    if let Some(Command::Sprt { candidate, baseline, elo0, elo1, alpha, beta, max_pairs, threads }) = &cli.command {
+        let test = Sprt::new(*elo0, *elo1, *alpha, *beta);
+        let (lower, upper) = test.bounds();
+        let seed = cli.seed.unwrap_or_else(rand::random);
+        println!("Seed: {}", seed);
+        println!("{} vs {}, H0: elo <= {}, H1: elo >= {}, bounds ({:.2}, {:.2})", candidate, baseline, elo0, elo1, lower, upper);
+        let mut runner = Batch::new(|name| registry.create(name).map(with_book), make_judge);
+        if let Some(threads) = threads {
+            runner.threads = *threads;
+        }
+        let result = sprt::run(&runner, &test, candidate, baseline, *max_pairs, seed, |results, llr| {
+            println!("{} pairs {:?}, score {:.3}, elo {:.1}, llr {:.2}",
+                results.pairs(), results.counts, results.score().unwrap_or(0.5), results.elo().unwrap_or(0.0), llr);
+        });
+        match result {
+            Ok(result) => match result.status {
+                sprt::Status::AcceptH1 => println!("H1 accepted, {} is stronger", candidate),
+                sprt::Status::AcceptH0 => println!("H0 accepted, {} is not stronger", candidate),
+                sprt::Status::Continue => println!("No decision after {} pairs", result.results.pairs()),
+            },
+            Err(e) => println!("Could not play the games: {}", e),
+        }
+        return;
+    }
+
+    if let Some(Command::Analyze { fen }) = &cli.command {
+        let Some(game) = position(fen.as_deref()) else {
+            return;
+        };
+        let seed = cli.seed.unwrap_or_else(rand::random);
+        rng::seed(seed);
+        println!("Seed: {}", seed);
+        analyze(&game, &registry);
+        return;
+    }
+
+    if let Some(Command::Explain { strategy, fen, format }) = &cli.command {
+        let Some(game) = position(fen.as_deref()) else {
+            return;
+        };
+        let Some(mut bot) = registry.create(strategy) else {
+            println!("Unknown strategy: {}", strategy);
+            return;
+        };
+        let seed = cli.seed.unwrap_or_else(rand::random);
+        rng::seed(seed);
+        // on stderr so the json and dot can be piped somewhere
+        eprintln!("Seed: {}", seed);
+        match (bot.trace(&game), format) {
+            (None, _) => println!("{} doesn't look at any values to pick its moves", bot.name()),
+            (Some(trace), TraceFormat::Text) => print!("{}", trace),
+            (Some(trace), TraceFormat::Json) => println!("{}", trace.to_json()),
+            (Some(trace), TraceFormat::Dot) => print!("{}", trace.to_dot()),
+        }
+        return;
+    }
+
     if cli.list {
         println!("Available strategies:");
-        /*
-        for s in all::<Strategy>() {
+        for s in registry.entries() {
             println!("{} - {}", s.name(), s.description());
         }
-        */
     } else {
         let white = cli.white_player;
         let black = cli.black_player;
 
-        if let (Some(white_strat), Some(black_strat)) = (strategy_map().get(&white), strategy_map().get(&black)) {
-            let (_, state) = play_game(black_strat, white_strat);
-            println!("Final state: {:?}", state);
+        if let (Some(white_strat), Some(black_strat)) = (registry.create(&white), registry.create(&black)) {
+            let (white_strat, black_strat) = (with_book(white_strat), with_book(black_strat));
+            #[cfg(feature = "syzygy")]
+            let (white_strat, black_strat) = (with_tables(white_strat, cli.white_endgame), with_tables(black_strat, cli.black_endgame));
+            let (mut white_strat, mut black_strat) = (white_strat, black_strat);
+            let seed = cli.seed.unwrap_or_else(rand::random);
+            rng::seed(seed);
+            println!("Seed: {}", seed);
+            let start = Instant::now();
+            #[cfg(feature = "tui")]
+            if cli.tui {
+                match tui::play(black_strat.as_mut(), white_strat.as_mut(), make_judge().as_mut()) {
+                    Ok((game, Some(result))) => {
+                        println!("Final state: {:?} ({:?})", result.state, result.reason);
+                        keep(ArchivedGame::new(&white, &black, seed, &game.moves, result, start.elapsed()));
+                    }
+                    Ok((_, None)) => println!("Stopped before the end"),
+                    Err(e) => println!("Could not use the terminal: {}", e),
+                }
+                return;
+            }
+            let (game, result) = play_game_adjudicated(black_strat.as_mut(), white_strat.as_mut(), make_judge().as_mut());
+            println!("Final state: {:?} ({:?})", result.state, result.reason);
+            keep(ArchivedGame::new(&white, &black, seed, &game.moves, result, start.elapsed()));
         } else {
             println!("Could not find one of the given strategies: {} + {}", white, black);
             println!("Options for strategies are:");
-            /*
-            for s in all::<Strategy>() {
+            for s in registry.entries() {
                 println!("{}", s.name());
             }
-            */
+        }
+    }
+}
+
+fn print_results(players: &[String], records: &[batch::GameRecord]) {
+    println!();
+    println!("{:>4} {:<24} {:>6} {:>6} {:>6} {:>6} {:>7}", "", "Strategy", "Games", "Wins", "Draws", "Losses", "Score");
+    for (i, (name, tally)) in batch::standings(records).iter().enumerate() {
+        println!("{:>4} {:<24} {:>6} {:>6} {:>6} {:>6} {:>7.1}", i + 1, name, tally.games(), tally.wins, tally.draws, tally.losses, tally.score());
+    }
+
+    // white's score against each opponent, the columns are the rows' numbers
+    println!();
+    let table = batch::cross_table(records);
+    let header: String = (1..=players.len()).map(|i| format!("{:>5}", i)).collect();
+    println!("{:>4} {:<24}{}", "", "White \\ Black", header);
+    for (i, white) in players.iter().enumerate() {
+        let row: String = players.iter()
+            .map(|black| match table.get(&(white.clone(), black.clone())) {
+                Some(tally) => format!("{:>5.1}", tally.score()),
+                None => format!("{:>5}", "-"),
+            })
+            .collect();
+        println!("{:>4} {:<24}{}", i + 1, white, row);
+    }
+}
+
+fn show_games(archive: &Archive, query: &Query, pgn: Option<&Path>) {
+    let games = match archive.games() {
+        Ok(games) => games,
+        Err(e) => {
+            println!("Could not read {}: {}", archive.path().display(), e);
+            return;
+        }
+    };
+    let found: Vec<_> = games.iter().enumerate().filter(|(_, g)| query.matches(g)).collect();
+
+    let Some(pgn) = pgn else {
+        for (i, g) in &found {
+            println!("{:>5} {} vs {}: {} ({:?}) in {} plies, seed {}",
+                i + 1, g.white, g.black, archive::result_text(g.result), g.result.reason, g.plies, g.seed);
+        }
+        println!("{} of {} games", found.len(), games.len());
+        return;
+    };
+
+    let written: Vec<String> = found.iter()
+        .filter_map(|(i, g)| {
+            let pgn = archive::to_pgn(g);
+            if pgn.is_none() {
+                println!("Game {} has moves that can't be played, skipping it", i + 1);
+            }
+            pgn
+        })
+        .collect();
+    let text = written.concat();
+    if pgn == Path::new("-") {
+        print!("{}", text);
+    } else if let Err(e) = std::fs::write(pgn, text) {
+        println!("Could not write {}: {}", pgn.display(), e);
+    } else {
+        println!("Wrote {} games to {}", written.len(), pgn.display());
+    }
+}
+
+// the starting position without a fen
+fn position(fen: Option<&str>) -> Option<ChessGame> {
+    match fen.map(notation::from_fen) {
+        Some(Ok(game)) => Some(game),
+        Some(Err(e)) => {
+            println!("Could not read the fen: {}", e);
+            None
+        }
+        None => Some(ChessGame::new()),
+    }
+}
+
+fn analyze(game: &ChessGame, registry: &Registry) {
+    println!("{}", game);
+    println!("{}", notation::to_fen(game));
+    if let Some(result) = game.result() {
+        println!("The game is over: {:?} ({:?})", result.state, result.reason);
+        return;
+    }
+
+    // every score is from the side of the player to move
+    let names: Vec<_> = analysis::evaluators().into_iter().map(|(name, _)| name).collect();
+    let header: String = names.iter().map(|n| format!(" {:>10}", n)).collect();
+    println!();
+    println!("{:<8}{}", "Move", header);
+    for m in analysis::score_moves(game) {
+        let scores: String = m.scores.iter().map(|s| format!(" {:>10}", s)).collect();
+        println!("{:<8}{}", m.san, scores);
+    }
+
+    println!();
+    for (name, choice) in analysis::choices(game, registry) {
+        match choice {
+            Some(act) => println!("{:<24} {}", name, notation::san(game, act)),
+            None => println!("{:<24} gives up", name),
         }
     }
 }
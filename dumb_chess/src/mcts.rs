@@ -0,0 +1,186 @@
+use rand::seq::SliceRandom;
+
+use crate::bot::Bot;
+use crate::rng::with_rng;
+use crate::final_state::FinalState::{self, *};
+use crate::player::Player;
+use crate::{game::ChessGame, action::Action};
+
+// monte carlo tree search
+// doesn't know anything about chess other than how the game ends, it plays a
+// bunch of games out from the current position and goes with the move that
+// looked the most promising while doing so
+// the playouts are random moves unless another bot is given to make them
+
+pub struct Mcts {
+    name: String,
+    description: String,
+    pub iterations: u32,
+    // how much to favor trying less explored moves over the known good ones
+    pub exploration: f32,
+    // playouts that go on longer than this are called a draw
+    pub max_playout: u32,
+    rollout: Option<Box<dyn Bot>>,
+}
+
+struct Node {
+    game: ChessGame,
+    action: Option<Action>,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    untried: Vec<Action>,
+    // who made the move into this node, the score is from their side
+    mover: Player,
+    visits: u32,
+    score: f32,
+}
+
+impl Node {
+    fn new(game: ChessGame, action: Option<Action>, parent: Option<usize>) -> Self {
+        let mut untried: Vec<Action> = if game.check_state().is_some() {
+            Vec::new()
+        } else {
+            game.possible_moves(game.turn).collect()
+        };
+        with_rng(|rng| untried.shuffle(rng));
+        let mover = game.turn.toggle();
+        Node { game, action, parent, children: Vec::new(), untried, mover, visits: 0, score: 0.0 }
+    }
+
+    fn uct(&self, parent_visits: u32, exploration: f32) -> f32 {
+        let visits = self.visits as f32;
+        self.score/visits + exploration*((parent_visits as f32).ln()/visits).sqrt()
+    }
+}
+
+impl Mcts {
+    pub const DESCRIPTION: &'static str = "Plays lots of random games in its head and goes with what worked.";
+
+    pub fn new(iterations: u32) -> Self {
+        Mcts {
+            name: "MCTS".to_string(),
+            description: Self::DESCRIPTION.to_string(),
+            iterations,
+            exploration: std::f32::consts::SQRT_2,
+            max_playout: 60,
+            rollout: None,
+        }
+    }
+
+    // playouts are made by the given bot instead of randomly
+    pub fn with_rollout(iterations: u32, rollout: Box<dyn Bot>) -> Self {
+        Mcts {
+            name: format!("MCTS {}", rollout.name()),
+            description: Self::rollout_description(rollout.name()),
+            rollout: Some(rollout),
+            ..Self::new(iterations)
+        }
+    }
+
+    // what a bot with playouts made by the named bot says about itself
+    pub fn rollout_description(rollout: &str) -> String {
+        format!("Imagines lots of games where everyone plays like {} and goes with what worked.", rollout)
+    }
+
+    fn search(&mut self, game: &ChessGame) -> Option<Action> {
+        let mut nodes = vec![Node::new(game.clone(), None, None)];
+        for _ in 0..self.iterations {
+            // selection, follow the best looking children until something
+            // still has moves that haven't been tried
+            let mut current = 0;
+            while nodes[current].untried.is_empty() && !nodes[current].children.is_empty() {
+                let parent_visits = nodes[current].visits;
+                current = *nodes[current].children.iter()
+                    .max_by(|a, b| {
+                        let a = nodes[**a].uct(parent_visits, self.exploration);
+                        let b = nodes[**b].uct(parent_visits, self.exploration);
+                        a.total_cmp(&b)
+                    })
+                    .unwrap();
+            }
+
+            // expansion
+            if let Some(act) = nodes[current].untried.pop() {
+                let child = Node::new(nodes[current].game.step(act), Some(act), Some(current));
+                nodes.push(child);
+                let index = nodes.len() - 1;
+                nodes[current].children.push(index);
+                current = index;
+            }
+
+            // simulation
+            let result = self.playout(&nodes[current].game);
+
+            // backpropagation
+            let mut next = Some(current);
+            while let Some(index) = next {
+                let node = &mut nodes[index];
+                node.visits += 1;
+                node.score += match result {
+                    Some(Win(p)) if p == node.mover => 1.0,
+                    Some(Win(_)) => 0.0,
+                    _ => 0.5,
+                };
+                next = node.parent;
+            }
+        }
+
+        // with no iterations nothing got tried, so any legal move will have to do
+        nodes[0].children.iter()
+            .max_by_key(|c| nodes[**c].visits)
+            .and_then(|c| nodes[*c].action)
+            .or_else(|| nodes[0].untried.last().copied())
+    }
+
+    // play the game out, None when it took too long to finish
+    fn playout(&mut self, game: &ChessGame) -> Option<FinalState> {
+        let mut game = game.clone();
+        for _ in 0..self.max_playout {
+            // the same endings Node::new stops at, like the fifty move rule
+            if let Some(state) = game.check_state() {
+                return Some(state);
+            }
+            let act = match &mut self.rollout {
+                Some(bot) => bot.choose(&game),
+                None => {
+                    let moves: Vec<Action> = game.possible_moves(game.turn).collect();
+                    with_rng(|rng| moves.choose(rng)).copied()
+                }
+            };
+            match act {
+                Some(act) => game = game.step(act),
+                // same as a bot giving up in play_game
+                None => return Some(game.check_state().unwrap_or(Draw)),
+            }
+        }
+        None
+    }
+}
+
+impl Bot for Mcts {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn choose(&mut self, game: &ChessGame) -> Option<Action> {
+        self.search(game)
+    }
+
+    fn new_game(&mut self) {
+        if let Some(bot) = &mut self.rollout {
+            bot.new_game();
+        }
+    }
+
+    fn model(&self) -> Option<Box<dyn Bot>> {
+        let copy = match &self.rollout {
+            Some(bot) => Mcts::with_rollout(self.iterations, bot.model()?),
+            None => Mcts::new(self.iterations),
+        };
+        Some(Box::new(Mcts { exploration: self.exploration, max_playout: self.max_playout, ..copy }))
+    }
+}
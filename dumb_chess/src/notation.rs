@@ -0,0 +1,533 @@
+use std::fmt::Display;
+
+use crate::action::{Action::{self, *}, Move};
+use crate::coord::Coord;
+use crate::game::ChessGame;
+use crate::piece::Piece::{self, *};
+use crate::player::Player::{self, *};
+
+// converting to and from the usual ways of writing chess down
+// the board is stored with black on top, so row 0 is rank 8 and col 0 is file a
+
+pub fn square_name(coord: Coord) -> String {
+    let file = (b'a' + coord.col as u8) as char;
+    let rank = 8 - coord.row;
+    format!("{}{}", file, rank)
+}
+
+pub fn parse_square(square: &str) -> Option<Coord> {
+    let bytes = square.as_bytes();
+    if bytes.len() != 2 {
+        return None;
+    }
+    let col = bytes[0].checked_sub(b'a')? as i32;
+    let rank = bytes[1].checked_sub(b'0')? as i32;
+    if !(0..8).contains(&col) || !(1..=8).contains(&rank) {
+        return None;
+    }
+    Some(Coord::new(8 - rank, col))
+}
+
+// where the move starts, where it ends, and what a pawn turns into
+// castling is the king's move
+pub fn move_squares(action: Action) -> (Coord, Coord, Option<Piece>) {
+    match action {
+        AMove(m) => (m.from, m.to, None),
+        ATake(m) => (m.from, m.to, None),
+        ACastle(m) => (m.king_from, m.king_to, None),
+        APromote(m) => (m.from, m.to, Some(m.end)),
+        APromoteTake(m) => (m.from, m.to, Some(m.end)),
+        APassant(m) => (m.from, m.to, None),
+    }
+}
+
+pub fn uci(action: Action) -> String {
+    let (from, to, promotion) = move_squares(action);
+    let mut s = format!("{}{}", square_name(from), square_name(to));
+    if let Some(p) = promotion {
+        s.push(piece_letter(p).to_ascii_lowercase());
+    }
+    s
+}
+
+// find the legal move written in uci
+pub fn parse_uci(game: &ChessGame, text: &str) -> Option<Action> {
+    game.possible_moves(game.turn).find(|m| uci(*m) == text)
+}
+
+// find the legal move written in san, the check marks and any !? after it
+// don't matter, and castling can be written with zeros
+pub fn parse_san(game: &ChessGame, text: &str) -> Option<Action> {
+    let text = plain_san(text);
+    game.possible_moves(game.turn).find(|m| plain_san(&san(game, *m)) == text)
+}
+
+// no square has a 0 in it, so only castling changes
+fn plain_san(text: &str) -> String {
+    text.trim_end_matches(['+', '#', '!', '?']).replace('0', "O")
+}
+
+// uppercase for white like in fen
+pub fn piece_letter(piece: Piece) -> char {
+    let letter = match piece {
+        Empty => ' ',
+        Pawn(_, _) => 'p',
+        Rook(_, _) => 'r',
+        Knight(_, _) => 'n',
+        Bishop(_, _) => 'b',
+        Queen(_, _) => 'q',
+        King(_, _) => 'k',
+    };
+    if piece.owned_by(White) {
+        letter.to_ascii_uppercase()
+    } else {
+        letter
+    }
+}
+
+pub fn to_fen(game: &ChessGame) -> String {
+    let mut rows = Vec::new();
+    for row in 0..8 {
+        let mut line = String::new();
+        let mut empty = 0;
+        for col in 0..8 {
+            let piece = game.board.get((row, col).into());
+            if piece.empty() {
+                empty += 1;
+            } else {
+                if empty > 0 {
+                    line.push_str(&empty.to_string());
+                    empty = 0;
+                }
+                line.push(piece_letter(piece));
+            }
+        }
+        if empty > 0 {
+            line.push_str(&empty.to_string());
+        }
+        rows.push(line);
+    }
+
+    let turn = match game.turn {
+        White => "w",
+        Black => "b",
+    };
+
+    let mut castling = String::new();
+    for (player, row) in [(White, 7), (Black, 0)] {
+        for (col, letter) in [(7, 'k'), (0, 'q')] {
+            if can_castle_later(game, player, row, col) {
+                castling.push(if player == White { letter.to_ascii_uppercase() } else { letter });
+            }
+        }
+    }
+    if castling.is_empty() {
+        castling.push('-');
+    }
+
+    let passant = match game.last_move() {
+        Some(AMove(m)) if matches!(m.piece, Pawn(_, _)) && (m.from.row - m.to.row).abs() == 2 => {
+            square_name(Coord::new((m.from.row + m.to.row)/2, m.from.col))
+        }
+        _ => "-".to_string(),
+    };
+
+    let halfmoves = game.halfmoves();
+    let fullmoves = game.turn_number/2 + 1;
+
+    format!("{} {} {} {} {} {}", rows.join("/"), turn, castling, passant, halfmoves, fullmoves)
+}
+
+// standard algebraic notation, the game is the one the move is made in
+pub fn san(game: &ChessGame, action: Action) -> String {
+    let mut text = match action {
+        ACastle(c) if c.rook_from.col == 7 => "O-O".to_string(),
+        ACastle(_) => "O-O-O".to_string(),
+        _ => {
+            let (from, to, promotion) = move_squares(action);
+            let piece = game.board.get(from);
+            let capture = matches!(action, ATake(_) | APromoteTake(_) | APassant(_));
+            let mut text = String::new();
+            if matches!(piece, Pawn(_, _)) {
+                if capture {
+                    text.push(square_name(from).as_bytes()[0] as char);
+                }
+            } else {
+                text.push(piece_letter(piece).to_ascii_uppercase());
+                text.push_str(&disambiguate(game, action, piece, from, to));
+            }
+            if capture {
+                text.push('x');
+            }
+            text.push_str(&square_name(to));
+            if let Some(p) = promotion {
+                text.push('=');
+                text.push(piece_letter(p).to_ascii_uppercase());
+            }
+            text
+        }
+    };
+    let after = game.step(action);
+    if after.in_check(after.turn) {
+        if after.possible_moves(after.turn).next().is_none() {
+            text.push('#');
+        } else {
+            text.push('+');
+        }
+    }
+    text
+}
+
+// enough of the starting square to tell the move apart from the same kind
+// of piece moving to the same place
+fn disambiguate(game: &ChessGame, action: Action, piece: Piece, from: Coord, to: Coord) -> String {
+    let others: Vec<Coord> = game.possible_moves(game.turn)
+        .filter(|m| *m != action && !matches!(m, ACastle(_)))
+        .map(move_squares)
+        .filter(|(f, t, _)| *t == to && *f != from
+            && std::mem::discriminant(&game.board.get(*f)) == std::mem::discriminant(&piece))
+        .map(|(f, _, _)| f)
+        .collect();
+    let name = square_name(from);
+    if others.is_empty() {
+        String::new()
+    } else if others.iter().all(|c| c.col != from.col) {
+        name[..1].to_string()
+    } else if others.iter().all(|c| c.row != from.row) {
+        name[1..].to_string()
+    } else {
+        name
+    }
+}
+
+// the moves numbered the way pgn writes them, starting from the usual position
+pub fn movetext(moves: &[Action]) -> String {
+    movetext_from(&ChessGame::new(), moves)
+}
+
+// the same from any position, which might have black moving first
+pub fn movetext_from(start: &ChessGame, moves: &[Action]) -> String {
+    let mut game = start.clone();
+    let mut parts = Vec::new();
+    for (i, act) in moves.iter().enumerate() {
+        let number = game.turn_number / 2 + 1;
+        if game.turn == White {
+            parts.push(format!("{}.", number));
+        } else if i == 0 {
+            parts.push(format!("{}...", number));
+        }
+        parts.push(san(&game, *act));
+        game = game.step(*act);
+    }
+    parts.join(" ")
+}
+
+// a whole game in pgn with the tags in the order given, a game that didn't
+// start from the usual position gets SetUp and FEN tags after them
+pub fn write_pgn(tags: &[(&str, String)], start: &ChessGame, moves: &[Action], result: &str) -> String {
+    let mut tags = tags.to_vec();
+    let fen = to_fen(start);
+    if fen != to_fen(&ChessGame::new()) {
+        tags.push(("SetUp", "1".to_string()));
+        tags.push(("FEN", fen));
+    }
+
+    let mut pgn = String::new();
+    for (name, value) in tags {
+        pgn.push_str(&format!("[{} \"{}\"]\n", name, value.replace('\\', "\\\\").replace('"', "\\\"")));
+    }
+    pgn.push('\n');
+
+    // pgn lines shouldn't go past 80 characters
+    let text = movetext_from(start, moves);
+    let mut line = String::new();
+    for word in text.split(' ').filter(|w| !w.is_empty()).chain([result]) {
+        if !line.is_empty() && line.len() + word.len() + 1 > 80 {
+            pgn.push_str(&line);
+            pgn.push('\n');
+            line.clear();
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    pgn.push_str(&line);
+    pgn.push_str("\n\n");
+    pgn
+}
+
+// a game read back from pgn, only the first game if there are several
+#[derive(Debug, Clone)]
+pub struct Pgn {
+    pub tags: Vec<(String, String)>,
+    pub start: ChessGame,
+    pub moves: Vec<Action>,
+}
+
+impl Pgn {
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags.iter().find(|(n, _)| n == name).map(|(_, value)| value.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseFenError {
+    // the fields that have to be there
+    Missing(&'static str),
+    // the field and what was in it
+    Invalid(&'static str, String),
+}
+
+impl Display for ParseFenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseFenError::Missing(field) => write!(f, "no {} in the fen", field),
+            ParseFenError::Invalid(field, text) => write!(f, "bad {} in the fen: {}", field, text),
+        }
+    }
+}
+
+impl std::error::Error for ParseFenError {}
+
+#[derive(Debug)]
+pub enum ParsePgnError {
+    Fen(ParseFenError),
+    // the ply (from 1) and the move that couldn't be played there
+    Move(usize, String),
+}
+
+impl Display for ParsePgnError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParsePgnError::Fen(e) => write!(f, "bad FEN tag: {}", e),
+            ParsePgnError::Move(ply, text) => write!(f, "can't play {} at ply {}", text, ply),
+        }
+    }
+}
+
+impl std::error::Error for ParsePgnError {}
+
+// comments, variations and annotation glyphs are skipped, and the moves stop
+// at the result
+pub fn from_pgn(text: &str) -> Result<Pgn, ParsePgnError> {
+    let mut tags = Vec::new();
+    let mut movetext = String::new();
+    let mut seen_moves = false;
+    for line in text.lines() {
+        let line = line.trim();
+        if let Some(tag) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            // the tags of the next game
+            if seen_moves {
+                break;
+            }
+            if let Some((name, value)) = tag.split_once(' ') {
+                let value = value.trim();
+                let value = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')).unwrap_or(value);
+                tags.push((name.to_string(), unescape(value)));
+            }
+        } else if !line.starts_with('%') {
+            seen_moves |= !line.is_empty();
+            movetext.push_str(line);
+            movetext.push('\n');
+        }
+    }
+
+    let start = match tags.iter().find(|(name, _)| name == "FEN") {
+        Some((_, fen)) => from_fen(fen).map_err(ParsePgnError::Fen)?,
+        None => ChessGame::new(),
+    };
+    let mut game = start.clone();
+    let mut moves = Vec::new();
+    for word in without_comments(&movetext).split_whitespace() {
+        if ["1-0", "0-1", "1/2-1/2", "*"].contains(&word) {
+            break;
+        }
+        // moves never have dots in them, so anything before one is the number
+        let word = word.rsplit('.').next().unwrap_or(word);
+        if word.is_empty() || word.starts_with('$') || word.chars().all(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        let act = parse_san(&game, word).ok_or_else(|| ParsePgnError::Move(moves.len() + 1, word.to_string()))?;
+        game = game.step(act);
+        moves.push(act);
+    }
+    Ok(Pgn { tags, start, moves })
+}
+
+// tag values have a \ in front of quotes and other \s
+fn unescape(text: &str) -> String {
+    let mut out = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        out.push(if c == '\\' { chars.next().unwrap_or(c) } else { c });
+    }
+    out
+}
+
+// {comments}, ; comments to the end of the line and (variations), which can
+// have more variations inside them
+fn without_comments(text: &str) -> String {
+    let mut out = String::new();
+    let mut brace = false;
+    let mut line = false;
+    let mut depth = 0;
+    for c in text.chars() {
+        if brace {
+            brace = c != '}';
+        } else if line {
+            line = c != '\n';
+        } else {
+            match c {
+                '{' => brace = true,
+                ';' => line = true,
+                '(' => depth += 1,
+                ')' if depth > 0 => depth -= 1,
+                _ if depth > 0 => {}
+                _ => {
+                    out.push(c);
+                    continue;
+                }
+            }
+        }
+        // whatever was skipped still separates the words either side of it
+        out.push(' ');
+    }
+    out
+}
+
+// a game starting from the position in the fen, with no moves made yet
+// castling rights, en passant and the halfmove clock are usually worked out
+// from the moves, so they go in game.before instead
+pub fn from_fen(text: &str) -> Result<ChessGame, ParseFenError> {
+    let mut fields = text.split_whitespace();
+    let placement = fields.next().ok_or(ParseFenError::Missing("board"))?;
+    let turn = fields.next().ok_or(ParseFenError::Missing("turn"))?;
+    let castling = fields.next().ok_or(ParseFenError::Missing("castling"))?;
+    let passant = fields.next().ok_or(ParseFenError::Missing("en passant square"))?;
+    // the clocks are often left off
+    let halfmoves = fields.next().unwrap_or("0");
+    let fullmoves = fields.next().unwrap_or("1");
+    let bad = |field: &'static str, text: &str| ParseFenError::Invalid(field, text.to_string());
+
+    let rows: Vec<&str> = placement.split('/').collect();
+    if rows.len() != 8 {
+        return Err(bad("board", placement));
+    }
+    let mut squares = [[None; 8]; 8];
+    for (row, line) in rows.iter().enumerate() {
+        let mut col = 0;
+        for c in line.chars() {
+            match c.to_digit(10) {
+                Some(n @ 1..=8) => col += n as usize,
+                Some(_) => return Err(bad("board", placement)),
+                None if col < 8 && "pnbrqkPNBRQK".contains(c) => {
+                    squares[row][col] = Some(c);
+                    col += 1;
+                }
+                None => return Err(bad("board", placement)),
+            }
+        }
+        if col != 8 {
+            return Err(bad("board", placement));
+        }
+    }
+
+    let mut game = ChessGame::blank();
+    game.moves.clear();
+    // ids go from a1 along each rank
+    let mut id = 0;
+    for row in (0..8).rev() {
+        for col in 0..8 {
+            let Some(c) = squares[row as usize][col as usize] else {
+                continue;
+            };
+            let player = if c.is_ascii_uppercase() { White } else { Black };
+            let piece = match c.to_ascii_lowercase() {
+                'p' => Pawn(id, player),
+                'n' => Knight(id, player),
+                'b' => Bishop(id, player),
+                'r' => Rook(id, player),
+                'q' => Queen(id, player),
+                _ => King(id, player),
+            };
+            game.board.set(Coord::new(row, col), piece);
+            id += 1;
+        }
+    }
+
+    game.turn = match turn {
+        "w" => White,
+        "b" => Black,
+        _ => return Err(bad("turn", turn)),
+    };
+
+    // the rooks that can still castle
+    let mut rights = Vec::new();
+    if castling != "-" {
+        for c in castling.chars() {
+            rights.push(match c {
+                'K' => Coord::new(7, 7),
+                'Q' => Coord::new(7, 0),
+                'k' => Coord::new(0, 7),
+                'q' => Coord::new(0, 0),
+                _ => return Err(bad("castling", castling)),
+            });
+        }
+    }
+
+    // the square a pawn skipped over, so rank 6 with white to move and rank 3
+    // with black to move
+    let passant = match passant {
+        "-" => None,
+        square => {
+            let ep = parse_square(square).ok_or_else(|| bad("en passant square", square))?;
+            let row = if game.turn == White { 2 } else { 5 };
+            if ep.row != row {
+                return Err(bad("en passant square", square));
+            }
+            Some(ep)
+        }
+    };
+    let halfmoves: usize = halfmoves.parse().map_err(|_| bad("halfmove clock", halfmoves))?;
+    let fullmoves: u32 = fullmoves.parse().map_err(|_| bad("fullmove number", fullmoves))?;
+    game.turn_number = (fullmoves.max(1) - 1) * 2 + if game.turn == Black { 1 } else { 0 };
+
+    // the pieces that can't castle or move two squares anymore
+    for p in game.board.pieces() {
+        let Some(player) = p.piece.owner() else {
+            continue;
+        };
+        let home = if player == White { 7 } else { 0 };
+        let moved = match p.piece {
+            Pawn(_, _) => p.coord.row != home + player.pawn_dir().row,
+            King(_, _) => p.coord != Coord::new(home, 4)
+                || !rights.iter().any(|sq| sq.row == home),
+            Rook(_, _) => !rights.contains(&p.coord),
+            _ => false,
+        };
+        if moved {
+            game.before.moved.push(p.piece);
+        }
+    }
+    game.before.halfmoves = halfmoves;
+
+    // the pawn that can be taken en passant has to have just moved two squares
+    if let Some(ep) = passant {
+        let dir = game.turn.toggle().pawn_dir().row;
+        let to = Coord::new(ep.row + dir, ep.col);
+        let pawn = game.board.get(to);
+        if matches!(pawn, Pawn(_, p) if p != game.turn) {
+            game.before.last = Some(AMove(Move::new(pawn, Coord::new(ep.row - dir, ep.col), to)));
+        }
+    }
+    Ok(game)
+}
+
+// the king and the rook are both where they started and haven't moved
+pub(crate) fn can_castle_later(game: &ChessGame, player: Player, row: i32, rook_col: i32) -> bool {
+    let king = game.board.get((row, 4).into());
+    let rook = game.board.get((row, rook_col).into());
+    matches!(king, King(_, p) if p == player) && !game.has_moved(king)
+        && matches!(rook, Rook(_, p) if p == player) && !game.has_moved(rook)
+}
@@ -0,0 +1,116 @@
+use enum_iterator::all;
+
+use crate::bot::Bot;
+use crate::definition::Definition;
+use crate::exploit::{Exploiter, card_counter};
+use crate::mcts::Mcts;
+use crate::stateful::{Grudge, Tourist};
+use crate::strategy::Strategy;
+
+type Factory = Box<dyn Fn() -> Box<dyn Bot> + Send + Sync>;
+
+// a way to make a fresh bot by name
+// bots can hold state, so rather than handing out shared ones every game gets
+// its own from the factory
+pub struct Entry {
+    name: String,
+    description: String,
+    factory: Factory,
+}
+
+impl Entry {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn create(&self) -> Box<dyn Bot> {
+        (self.factory)()
+    }
+}
+
+// all the bots that can be picked from, in listing order
+pub struct Registry {
+    entries: Vec<Entry>,
+}
+
+impl Registry {
+    // just the built in strategies
+    pub fn new() -> Self {
+        let mut registry = Self::empty();
+        for strategy in all::<Strategy>() {
+            registry.register_bot(strategy);
+        }
+        registry.register_bot(Grudge::default());
+        registry.register_bot(Tourist::default());
+        registry.register_definitions(vec![card_counter()]);
+        registry.register("MCTS", Mcts::DESCRIPTION, || Box::new(Mcts::new(200)));
+        registry.register("MCTS Swarm", &Mcts::rollout_description(Strategy::Swarm.name()), || {
+            Box::new(Mcts::with_rollout(100, Box::new(Strategy::Swarm)))
+        });
+        registry
+    }
+
+    pub fn empty() -> Self {
+        Registry { entries: Vec::new() }
+    }
+
+    // registering a name that is already there replaces it in place
+    pub fn register<F>(&mut self, name: &str, description: &str, factory: F)
+    where F: Fn() -> Box<dyn Bot> + Send + Sync + 'static {
+        let entry = Entry { name: name.to_string(), description: description.to_string(), factory: Box::new(factory) };
+        if let Some(existing) = self.entries.iter_mut().find(|e| e.name == name) {
+            *existing = entry;
+        } else {
+            self.entries.push(entry);
+        }
+    }
+
+    // register a bot that starts every game as a copy of the given one
+    pub fn register_bot<B>(&mut self, bot: B)
+    where B: Bot + Clone + Send + Sync + 'static {
+        let name = bot.name().to_string();
+        let description = bot.description().to_string();
+        self.register(&name, &description, move || Box::new(bot.clone()));
+    }
+
+    pub fn register_definitions(&mut self, definitions: Vec<Definition>) {
+        for def in definitions {
+            if def.exploit.is_some() {
+                let (name, description) = (def.name.clone(), def.description.clone());
+                self.register(&name, &description, Self::exploiter(def));
+            } else {
+                self.register_bot(def);
+            }
+        }
+    }
+
+    fn exploiter(def: Definition) -> impl Fn() -> Box<dyn Bot> + Send + Sync {
+        move || Box::new(Exploiter::new(def.clone()))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Entry> {
+        self.entries.iter().find(|e| e.name == name)
+    }
+
+    pub fn create(&self, name: &str) -> Option<Box<dyn Bot>> {
+        self.get(name).map(Entry::create)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item=&Entry> {
+        self.entries.iter()
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
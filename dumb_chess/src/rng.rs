@@ -0,0 +1,28 @@
+use std::cell::RefCell;
+
+use rand::{rngs::StdRng, SeedableRng};
+
+// all the randomness used by the strategies goes through here so that a game
+// can be played again from the same seed
+// every thread has its own generator, seeded from entropy until told otherwise
+thread_local! {
+    static RNG: RefCell<StdRng> = RefCell::new(StdRng::from_entropy());
+}
+
+pub fn seed(seed: u64) {
+    RNG.with(|rng| *rng.borrow_mut() = StdRng::seed_from_u64(seed));
+}
+
+// don't call with_rng again from inside the closure
+pub fn with_rng<T>(f: impl FnOnce(&mut StdRng) -> T) -> T {
+    RNG.with(|rng| f(&mut rng.borrow_mut()))
+}
+
+// a separate seed for each game of a batch so any one of them can be played
+// again on its own, mixed (splitmix64) so neighbouring games aren't related
+pub fn game_seed(seed: u64, game: u64) -> u64 {
+    let mut z = seed.wrapping_add(game.wrapping_add(1).wrapping_mul(0x9e3779b97f4a7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+    z ^ (z >> 31)
+}
@@ -0,0 +1,183 @@
+use crate::batch::{Batch, BatchError, Game, GameRecord};
+use crate::final_state::FinalState::*;
+use crate::player::Player::{self, *};
+use crate::rng;
+
+// sequential probability ratio test, is the candidate stronger than the baseline?
+// games are played in pairs with the colors swapped and the same seed, so
+// the luck of the draw mostly cancels out, and the pairs are counted by how
+// many points the candidate got out of them (0, 1/2, 1, 3/2 or 2), which is
+// the pentanomial part
+// the log likelihood ratio is the normal approximation to the generalized
+// sprt with logistic elo, same as fishtest uses
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sprt {
+    // the candidate is no better than this (h0)
+    pub elo0: f64,
+    // the candidate is at least this much better (h1)
+    pub elo1: f64,
+    // chance of accepting h1 when h0 is true
+    pub alpha: f64,
+    // chance of accepting h0 when h1 is true
+    pub beta: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Continue,
+    // the candidate is stronger
+    AcceptH1,
+    // it isn't
+    AcceptH0,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Pentanomial {
+    // number of pairs where the candidate got 0, 1/2, 1, 3/2 and 2 points
+    pub counts: [u32; 5],
+}
+
+impl Pentanomial {
+    // points are 0, 0.5 or 1 for each game
+    pub fn add_pair(&mut self, first: f64, second: f64) {
+        self.counts[((first + second) * 2.0).round() as usize] += 1;
+    }
+
+    pub fn pairs(&self) -> u32 {
+        self.counts.iter().sum()
+    }
+
+    // the candidate's average score per game, and its variance per pair
+    fn stats(&self) -> Option<(f64, f64)> {
+        let n = self.pairs() as f64;
+        if n == 0.0 {
+            return None;
+        }
+        let probs = self.counts.map(|c| c as f64 / n);
+        let points = [0.0, 0.25, 0.5, 0.75, 1.0];
+        let mean: f64 = probs.iter().zip(points).map(|(p, x)| p * x).sum();
+        let variance: f64 = probs.iter().zip(points).map(|(p, x)| p * (x - mean).powi(2)).sum();
+        Some((mean, variance))
+    }
+
+    pub fn score(&self) -> Option<f64> {
+        self.stats().map(|(mean, _)| mean)
+    }
+
+    // elo difference the score works out to, infinite when every game went
+    // the same way
+    pub fn elo(&self) -> Option<f64> {
+        self.score().map(score_to_elo)
+    }
+}
+
+pub fn elo_to_score(elo: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-elo / 400.0))
+}
+
+pub fn score_to_elo(score: f64) -> f64 {
+    -400.0 * (1.0 / score - 1.0).log10()
+}
+
+impl Sprt {
+    pub fn new(elo0: f64, elo1: f64, alpha: f64, beta: f64) -> Self {
+        Sprt { elo0, elo1, alpha, beta }
+    }
+
+    // stop once the llr goes below the first or above the second
+    pub fn bounds(&self) -> (f64, f64) {
+        ((self.beta / (1.0 - self.alpha)).ln(), ((1.0 - self.beta) / self.alpha).ln())
+    }
+
+    pub fn llr(&self, results: &Pentanomial) -> f64 {
+        let Some((mean, variance)) = results.stats() else {
+            return 0.0;
+        };
+        // can't tell anything while every pair has gone exactly the same way
+        if variance <= 0.0 {
+            return 0.0;
+        }
+        let s0 = elo_to_score(self.elo0);
+        let s1 = elo_to_score(self.elo1);
+        results.pairs() as f64 * (s1 - s0) * (2.0 * mean - s0 - s1) / (2.0 * variance)
+    }
+
+    pub fn status(&self, results: &Pentanomial) -> Status {
+        let llr = self.llr(results);
+        let (lower, upper) = self.bounds();
+        if llr >= upper {
+            Status::AcceptH1
+        } else if llr <= lower {
+            Status::AcceptH0
+        } else {
+            Status::Continue
+        }
+    }
+}
+
+impl Default for Sprt {
+    fn default() -> Self {
+        Sprt::new(0.0, 10.0, 0.05, 0.05)
+    }
+}
+
+// the candidate plays white in the first game of each pair and black in the
+// second, both with the same seed
+pub fn pairs(candidate: &str, baseline: &str, from: u64, count: u64, seed: u64) -> Vec<Game> {
+    (from..from + count)
+        .flat_map(|pair| {
+            let seed = rng::game_seed(seed, pair);
+            [
+                Game { white: candidate.to_string(), black: baseline.to_string(), seed },
+                Game { white: baseline.to_string(), black: candidate.to_string(), seed },
+            ]
+        })
+        .collect()
+}
+
+fn points(record: &GameRecord, color: Player) -> f64 {
+    match record.result.state {
+        Win(p) if p == color => 1.0,
+        Win(_) => 0.0,
+        Draw => 0.5,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SprtResult {
+    pub status: Status,
+    pub results: Pentanomial,
+    pub llr: f64,
+}
+
+// plays pairs a chunk at a time until the test decides or max_pairs have
+// been played, progress hears about every chunk
+pub fn run(
+    batch: &Batch,
+    sprt: &Sprt,
+    candidate: &str,
+    baseline: &str,
+    max_pairs: u64,
+    seed: u64,
+    mut progress: impl FnMut(&Pentanomial, f64),
+) -> Result<SprtResult, BatchError> {
+    // enough to keep every thread busy
+    let chunk = batch.threads.max(1) as u64;
+    let mut results = Pentanomial::default();
+    let mut played = 0;
+    while played < max_pairs {
+        let count = chunk.min(max_pairs - played);
+        let games = pairs(candidate, baseline, played, count, seed);
+        let records = batch.run(&games, |_, _| {})?;
+        for pair in records.chunks(2) {
+            results.add_pair(points(&pair[0], White), points(&pair[1], Black));
+        }
+        played += count;
+        progress(&results, sprt.llr(&results));
+        if sprt.status(&results) != Status::Continue {
+            break;
+        }
+    }
+    Ok(SprtResult { status: sprt.status(&results), results, llr: sprt.llr(&results) })
+}
@@ -0,0 +1,165 @@
+use rand::{Rng, seq::SliceRandom};
+
+use crate::bot::Bot;
+use crate::coord::Coord;
+use crate::piece::{Piece, ID};
+use crate::rng::with_rng;
+use crate::player::Player::{self, *};
+use crate::strategy::{strategy, EvalGame, MaxChoose, MaxCombine, MinChoose, MinCombine};
+use crate::{game::ChessGame, action::Action};
+
+use Action::*;
+
+// strategies that remember things over the course of a game
+// everything they know gets thrown away in new_game
+
+// holds a grudge against every piece that took one of theirs and goes after
+// the most recent one until it is gone
+#[derive(Debug, Clone, Default)]
+pub struct Grudge {
+    grudges: Vec<ID>,
+    // how much of the move history has been looked through already
+    seen: usize,
+}
+
+// picks somewhere on the other side of the board before the first move and
+// sends everyone to see it
+#[derive(Debug, Clone, Default)]
+pub struct Tourist {
+    destination: Option<Coord>,
+}
+
+impl Grudge {
+    fn remember(&mut self, game: &ChessGame) {
+        let player = game.turn;
+        for act in game.moves.iter().skip(self.seen) {
+            if let Some((attacker, removed)) = capture(*act) {
+                if removed.owned_by(player) {
+                    if let Some(id) = attacker.id() {
+                        self.grudges.retain(|g| *g != id);
+                        self.grudges.push(id);
+                    }
+                }
+            }
+        }
+        self.seen = game.moves.len();
+    }
+
+    pub fn grudges(&self) -> &[ID] {
+        &self.grudges
+    }
+}
+
+impl Bot for Grudge {
+    fn name(&self) -> &str {
+        "Grudge"
+    }
+
+    fn description(&self) -> &str {
+        "Never forgets a piece that took one of theirs."
+    }
+
+    fn choose(&mut self, game: &ChessGame) -> Option<Action> {
+        self.remember(game);
+        let player = game.turn;
+        let target = self.grudges.iter().rev()
+            .copied()
+            .find(|id| find_piece(game, player.toggle(), *id).is_some());
+        match target {
+            Some(id) => strategy(1, player, game, &Revenge(player, id), &MaxChoose, &MaxCombine),
+            None => {
+                let moves: Vec<Action> = game.possible_moves(player).collect();
+                with_rng(|rng| moves.choose(rng)).copied()
+            }
+        }
+    }
+
+    fn new_game(&mut self) {
+        self.grudges.clear();
+        self.seen = 0;
+    }
+
+    fn model(&self) -> Option<Box<dyn Bot>> {
+        Some(Box::new(self.clone()))
+    }
+}
+
+impl Tourist {
+    pub fn destination(&self) -> Option<Coord> {
+        self.destination
+    }
+}
+
+impl Bot for Tourist {
+    fn name(&self) -> &str {
+        "Tourist"
+    }
+
+    fn description(&self) -> &str {
+        "Picked a spot to visit before the game started and everyone is going."
+    }
+
+    fn choose(&mut self, game: &ChessGame) -> Option<Action> {
+        let player = game.turn;
+        let destination = *self.destination.get_or_insert_with(|| with_rng(|rng| {
+            // the opponent starts at the top for white and the bottom for black
+            let row = match player {
+                White => rng.gen_range(0..4),
+                Black => rng.gen_range(4..8),
+            };
+            Coord::new(row, rng.gen_range(0..8))
+        }));
+        strategy(1, player, game, &Sightseeing(player, destination), &MinChoose, &MinCombine)
+    }
+
+    fn new_game(&mut self) {
+        self.destination = None;
+    }
+
+    fn model(&self) -> Option<Box<dyn Bot>> {
+        Some(Box::new(self.clone()))
+    }
+}
+
+// taking the piece is best, otherwise get close to it
+struct Revenge(Player, ID);
+// total distance from the player's pieces to the spot
+struct Sightseeing(Player, Coord);
+
+impl EvalGame for Revenge {
+    fn eval(&self, _action: Action, game: &ChessGame) -> f32 {
+        match find_piece(game, self.0.toggle(), self.1) {
+            Some(target) => {
+                let total: i32 = game.board.pieces_for(self.0)
+                    .map(|p| p.coord.man_dist(target))
+                    .sum();
+                -(total as f32)
+            }
+            None => f32::MAX,
+        }
+    }
+}
+
+impl EvalGame for Sightseeing {
+    fn eval(&self, _action: Action, game: &ChessGame) -> f32 {
+        game.board.pieces_for(self.0)
+            .map(|p| p.coord.man_dist(self.1))
+            .sum::<i32>() as f32
+    }
+}
+
+// the piece that did the taking and the piece that got taken
+fn capture(action: Action) -> Option<(Piece, Piece)> {
+    match action {
+        ATake(m) => Some((m.piece, m.removed)),
+        APromoteTake(m) => Some((m.end, m.removed)),
+        APassant(m) => Some((m.piece, m.removed)),
+        _ => None,
+    }
+}
+
+fn find_piece(game: &ChessGame, player: Player, id: ID) -> Option<Coord> {
+    game.board.pieces_for(player)
+        .find(|p| p.piece.id() == Some(id))
+        .map(|p| p.coord)
+}
@@ -1,13 +1,16 @@
 use core::f32;
-use std::collections::HashMap;
 
-use enum_iterator::{Sequence, all};
-use rand::{thread_rng, seq::SliceRandom};
+use enum_iterator::Sequence;
+use rand::{Rng, seq::SliceRandom, distributions::{Distribution, WeightedIndex}};
 use wasm_bindgen::prelude::wasm_bindgen;
 
-use crate::piece::{PieceData, ID};
+use crate::piece::{Piece, PieceData, ID};
+use crate::rng::with_rng;
 use crate::player::{Player};
 use crate::{game::ChessGame, action::Action};
+use crate::notation::{san, to_fen};
+use crate::definition::{Evaluator, Side::*};
+use crate::trace::{Node, StageTrace, Trace};
 use crate::piece::Piece::{*};
 
 use Action::*;
@@ -19,7 +22,7 @@ use Action::*;
 // quite a few of these could have mulitple steps of lookahead, but for now just
 // going to do specific amounts of lookahead for each thing or one step
 
-trait EvalGame {
+pub trait EvalGame {
     // evaluate the action and the resulting game
     fn eval(&self, action: Action, game: &ChessGame) -> f32;
 
@@ -29,13 +32,20 @@ trait EvalGame {
     }
 }
 
-trait ChooseMove {
+pub trait ChooseMove {
     // given the values computed for the top level actions pick the actions
     // examples would be the max value or min value
     fn choose_move(&self, left: (f32, Action), right: (f32, Action)) -> (f32, Action);
+
+    // pick from all of the candidates at once
+    // by default they are just compared in pairs, but something like picking
+    // with a probability needs to see everything
+    fn choose(&self, candidates: Vec<(f32, Action)>) -> Option<(f32, Action)> {
+        candidates.into_iter().reduce(|left, right| self.choose_move(left, right))
+    }
 }
 
-trait CombineValues {
+pub trait CombineValues {
     // given all computed values for the lower level actions combine them
     // examples would be the max, min, average, deviation
     fn combine_values(&self, values: Vec<f32>) -> f32;
@@ -44,30 +54,23 @@ trait CombineValues {
 
 // a simple lookahead strategy that only considers the given players moves for 
 // simplicities sake and because the other player's strategy is unknown
-fn strategy(depth: u8, player: Player, game: &ChessGame, eval: &impl EvalGame, choose: &impl ChooseMove, combine: &impl CombineValues) -> Option<Action> {
+pub fn strategy(depth: u8, player: Player, game: &ChessGame, eval: &(impl EvalGame + ?Sized), choose: &(impl ChooseMove + ?Sized), combine: &(impl CombineValues + ?Sized)) -> Option<Action> {
     let mut moves: Vec<Action> = game.possible_moves(player).collect();
-    moves.shuffle(&mut thread_rng());
-    if moves.is_empty() {
-        None
-    } else {
-        let mut choice = (strategy_lookahead(depth-1, player, moves[0], game, eval, combine), moves[0]);
-        for m in &moves[1..] {
-            let res = (strategy_lookahead(depth-1, player, *m, game, eval, combine), *m);
-            
-            choice = choose.choose_move(choice, res);
-        }
-        Some(choice.1)
-    }
+    with_rng(|rng| moves.shuffle(rng));
+    let values = moves.into_iter()
+        .map(|m| (strategy_lookahead(depth-1, player, m, game, eval, combine), m))
+        .collect();
+    choose.choose(values).map(|choice| choice.1)
 }
 
 
-fn strategy_lookahead(depth: u8, player: Player, act: Action, game: &ChessGame, eval: &impl EvalGame, combine: &impl CombineValues) -> f32 {
+pub fn strategy_lookahead(depth: u8, player: Player, act: Action, game: &ChessGame, eval: &(impl EvalGame + ?Sized), combine: &(impl CombineValues + ?Sized)) -> f32 {
     let g = game.step(act);
     if depth == 0 {
         eval.eval(act, &g)
     } else {
         let mut moves: Vec<Action> = g.possible_moves(player).collect();
-        moves.shuffle(&mut thread_rng());
+        with_rng(|rng| moves.shuffle(rng));
         if moves.is_empty() {
             eval.no_moves()
         } else {
@@ -78,6 +81,91 @@ fn strategy_lookahead(depth: u8, player: Player, act: Action, game: &ChessGame,
     }
 }
 
+// the same as strategy but keeping every value that went into picking the move
+pub fn strategy_traced(depth: u8, player: Player, game: &ChessGame, eval: &(impl EvalGame + ?Sized), choose: &(impl ChooseMove + ?Sized), combine: &(impl CombineValues + ?Sized)) -> Trace {
+    let mut moves: Vec<Action> = game.possible_moves(player).collect();
+    with_rng(|rng| moves.shuffle(rng));
+    let stage = stage_traced(depth, player, game, &moves, eval, choose, combine);
+    let chosen = stage.chosen;
+    Trace { player, fen: to_fen(game), stages: vec![stage], chosen }
+}
+
+pub fn strategy_lookahead_traced(depth: u8, player: Player, act: Action, game: &ChessGame, eval: &(impl EvalGame + ?Sized), combine: &(impl CombineValues + ?Sized)) -> Node {
+    let g = game.step(act);
+    let name = san(game, act);
+    if depth == 0 {
+        Node::new(act, name, eval.eval(act, &g), Vec::new())
+    } else {
+        let mut moves: Vec<Action> = g.possible_moves(player).collect();
+        with_rng(|rng| moves.shuffle(rng));
+        if moves.is_empty() {
+            Node::new(act, name, eval.no_moves(), Vec::new())
+        } else {
+            let children: Vec<Node> = moves.into_iter()
+                .map(|m| strategy_lookahead_traced(depth-1, player, m, &g, eval, combine)).collect();
+            let value = combine.combine_values(children.iter().map(|c| c.value).collect());
+            Node::new(act, name, value, children)
+        }
+    }
+}
+
+fn stage_traced(depth: u8, player: Player, game: &ChessGame, moves: &[Action], eval: &(impl EvalGame + ?Sized), choose: &(impl ChooseMove + ?Sized), combine: &(impl CombineValues + ?Sized)) -> StageTrace {
+    let candidates: Vec<Node> = moves.iter()
+        .map(|m| strategy_lookahead_traced(depth-1, player, *m, game, eval, combine))
+        .collect();
+    let chosen = choose.choose(candidates.iter().map(|c| (c.value, c.action)).collect());
+    StageTrace::new(depth, candidates, chosen)
+}
+
+// one step of a lexicographic strategy, the moves that tie for the best value
+// get handed to the next stage to break the tie
+pub struct Stage<'a> {
+    pub depth: u8,
+    pub eval: &'a dyn EvalGame,
+    pub choose: &'a dyn ChooseMove,
+    pub combine: &'a dyn CombineValues,
+}
+
+// like strategy, but goes through the stages in order only keeping the moves
+// that did the best according to the previous stages
+// a remaining tie is broken randomly
+pub fn strategy_lexicographic(player: Player, game: &ChessGame, stages: &[Stage]) -> Option<Action> {
+    let mut moves: Vec<Action> = game.possible_moves(player).collect();
+    with_rng(|rng| moves.shuffle(rng));
+    for stage in stages {
+        if moves.len() <= 1 {
+            break;
+        }
+        let values: Vec<(f32, Action)> = moves.iter()
+            .map(|m| (strategy_lookahead(stage.depth-1, player, *m, game, stage.eval, stage.combine), *m))
+            .collect();
+        let best = stage.choose.choose(values.clone()).unwrap();
+        // the chosen move is kept explicitly in case the value can't be compared (NaN)
+        moves = values.into_iter()
+            .filter(|(v, m)| *v == best.0 || *m == best.1)
+            .map(|(_, m)| m)
+            .collect();
+    }
+    moves.first().copied()
+}
+
+// the same as strategy_lexicographic, with a stage in the trace for every
+// stage that had more than one move to pick from
+pub fn strategy_lexicographic_traced(player: Player, game: &ChessGame, stages: &[Stage]) -> Trace {
+    let mut moves: Vec<Action> = game.possible_moves(player).collect();
+    with_rng(|rng| moves.shuffle(rng));
+    let mut traced = Vec::new();
+    for stage in stages {
+        if moves.len() <= 1 {
+            break;
+        }
+        let t = stage_traced(stage.depth, player, game, &moves, stage.eval, stage.choose, stage.combine);
+        moves = t.tied.clone();
+        traced.push(t);
+    }
+    Trace { player, fen: to_fen(game), stages: traced, chosen: moves.first().copied() }
+}
+
 #[wasm_bindgen]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Sequence)]
 pub enum Strategy {
@@ -112,14 +200,6 @@ pub enum Strategy {
 
 use Strategy::*;
 
-pub fn strategy_map() -> HashMap<String, Strategy> {
-    let mut map = HashMap::new();
-    for strategy in all::<Strategy>() {
-        map.insert(strategy.name().to_string(), strategy);
-    }
-    map
-}
-
 impl Strategy {
     pub fn name(&self) -> &'static str {
         match self {
@@ -180,91 +260,78 @@ impl Strategy {
         }
     }
     pub fn run(&self, game: &ChessGame) -> Option<Action> {
+        if let Some(l) = self.lookahead() {
+            let eval = l.evaluator.build(game.turn);
+            return strategy(l.depth, game.turn, game, eval.as_ref(), l.choose.as_ref(), l.combine.as_ref());
+        }
         match self {
-            Random           => {
+            Random => {
                 let moves: Vec<Action> = game.possible_moves(game.turn).collect();
                 if !moves.is_empty() {
-                    Some(*moves.choose(&mut thread_rng()).unwrap())
+                    Some(*with_rng(|rng| moves.choose(rng)).unwrap())
                 } else {
                     None
                 }
             },
-            Swarm => {
-                let player = game.turn;
-                strategy(1, player, game, &KingDistance(player, player.toggle()), &MinChoose, &MinCombine)
-            },
-            GiveUp => {
-                None
-            },
-            Huddle => {
-                let player = game.turn;
-                strategy(1, player, game, &KingDistance(player, player), &MinChoose, &MinCombine)
-            },
-            SmellyKing => {
-                let player = game.turn;
-                strategy(1, player, game, &KingDistance(player, player), &MaxChoose, &MaxCombine)
-            },
-            Intimidated => {
-                let player = game.turn;
-                strategy(1, player, game, &KingDistance(player, player.toggle()), &MaxChoose, &MaxCombine)
-            },
-            Ape => {
-                strategy(1, game.turn, game, &BigPlays, &MaxChoose, &MaxCombine)
-            },
-            Sleepy => {
-                strategy(1, game.turn, game, &BigPlays, &MinChoose, &MinCombine)
-            },
-            Pacifist => {
-                strategy(1, game.turn, game, &Attacks, &MinChoose, &SumCombine)
-            },
-            EqualOpportunity => {
-                strategy(1, game.turn, game, &CountMoves, &MinChoose, &MinCombine)
-            },
-            Momentum => {
-                strategy(1, game.turn, game, &CountMoves, &MaxChoose, &MaxCombine)
-            },
-            Prepared => {
-                strategy(1, game.turn, game, &Attacks, &MaxChoose, &SumCombine)
-            },
-            Lawyer => {
-                let player = game.turn;
-                strategy(1, player, game, &MoveAmount(player), &MaxChoose, &MaxCombine)
-            }
-            Criminal => {
-                let player = game.turn;
-                strategy(1, player, game, &MoveAmount(player), &MinChoose, &MinCombine)
-            }
-            Paralegal => {
-                let player = game.turn;
-                strategy(1, player, game, &MoveAmount(player.toggle()), &MaxChoose, &MaxCombine)
-            }
-            UndercoverCop => {
-                let player = game.turn;
-                strategy(1, player, game, &MoveAmount(player.toggle()), &MinChoose, &MinCombine)
-            }
-            DrunkKing => {
-                strategy(1, game.turn, game, &KingMoves(game.turn), &MaxChoose, &SumCombine)
-            }
-            Polite => {
-                strategy(1, game.turn, game, &KingMoves(game.turn.toggle()), &MaxChoose, &SumCombine)
-            }
-            ElderlyKing => {
-                strategy(1, game.turn, game, &KingMoves(game.turn), &MinChoose, &SumCombine)
-            }
-            Shutdown => {
-                strategy(1, game.turn, game, &KingMoves(game.turn.toggle()), &MinChoose, &SumCombine)
-            }
-            LadiesFirst => {
-                strategy(1, game.turn, game, &QueenMoves, &MaxChoose, &SumCombine)
-            }
-            Offensive => {
-                strategy(2, game.turn, game, &InCheck(game.turn.toggle()), &MaxChoose, &MaxCombine)
-            }
-            Defensive => {
-                strategy(2, game.turn, game, &InCheck(game.turn), &MinChoose, &MinCombine)
-            }
+            _ => None,
         }
     }
+
+    // what went into the choice run would make, the same move with the same
+    // seed, None for the strategies that don't look at any values
+    pub fn trace(&self, game: &ChessGame) -> Option<Trace> {
+        let l = self.lookahead()?;
+        let eval = l.evaluator.build(game.turn);
+        Some(strategy_traced(l.depth, game.turn, game, eval.as_ref(), l.choose.as_ref(), l.combine.as_ref()))
+    }
+
+    // what the strategy is looking at, the same as a definition would write it
+    pub fn evaluator(&self) -> Option<Evaluator> {
+        self.lookahead().map(|l| l.evaluator)
+    }
+
+    fn lookahead(&self) -> Option<Lookahead> {
+        let l = match self {
+            Random | GiveUp  => return None,
+            Swarm            => Lookahead::new(1, Evaluator::KingDistance(Me, Opponent), MinChoose, MinCombine),
+            Huddle           => Lookahead::new(1, Evaluator::KingDistance(Me, Me), MinChoose, MinCombine),
+            SmellyKing       => Lookahead::new(1, Evaluator::KingDistance(Me, Me), MaxChoose, MaxCombine),
+            Intimidated      => Lookahead::new(1, Evaluator::KingDistance(Me, Opponent), MaxChoose, MaxCombine),
+            Ape              => Lookahead::new(1, Evaluator::BigPlays, MaxChoose, MaxCombine),
+            Sleepy           => Lookahead::new(1, Evaluator::BigPlays, MinChoose, MinCombine),
+            Pacifist         => Lookahead::new(1, Evaluator::Attacks, MinChoose, SumCombine),
+            EqualOpportunity => Lookahead::new(1, Evaluator::CountMoves, MinChoose, MinCombine),
+            Momentum         => Lookahead::new(1, Evaluator::CountMoves, MaxChoose, MaxCombine),
+            Prepared         => Lookahead::new(1, Evaluator::Attacks, MaxChoose, SumCombine),
+            Lawyer           => Lookahead::new(1, Evaluator::MoveAmount(Me), MaxChoose, MaxCombine),
+            Criminal         => Lookahead::new(1, Evaluator::MoveAmount(Me), MinChoose, MinCombine),
+            Paralegal        => Lookahead::new(1, Evaluator::MoveAmount(Opponent), MaxChoose, MaxCombine),
+            UndercoverCop    => Lookahead::new(1, Evaluator::MoveAmount(Opponent), MinChoose, MinCombine),
+            DrunkKing        => Lookahead::new(1, Evaluator::KingMoves(Me), MaxChoose, SumCombine),
+            Polite           => Lookahead::new(1, Evaluator::KingMoves(Opponent), MaxChoose, SumCombine),
+            ElderlyKing      => Lookahead::new(1, Evaluator::KingMoves(Me), MinChoose, SumCombine),
+            Shutdown         => Lookahead::new(1, Evaluator::KingMoves(Opponent), MinChoose, SumCombine),
+            LadiesFirst      => Lookahead::new(1, Evaluator::QueenMoves, MaxChoose, SumCombine),
+            Offensive        => Lookahead::new(2, Evaluator::InCheck(Opponent), MaxChoose, MaxCombine),
+            Defensive        => Lookahead::new(2, Evaluator::InCheck(Me), MinChoose, MinCombine),
+        };
+        Some(l)
+    }
+}
+
+// the pieces of a strategy that looks ahead, the evaluator is built once it's
+// known who is moving
+struct Lookahead {
+    depth: u8,
+    evaluator: Evaluator,
+    choose: Box<dyn ChooseMove>,
+    combine: Box<dyn CombineValues>,
+}
+
+impl Lookahead {
+    fn new(depth: u8, evaluator: Evaluator, choose: impl ChooseMove + 'static, combine: impl CombineValues + 'static) -> Self {
+        Lookahead { depth, evaluator, choose: Box::new(choose), combine: Box::new(combine) }
+    }
 }
 
 // since there is a default sort, just use that sort
@@ -295,14 +362,16 @@ pub fn rev_sorted_player(game: &ChessGame) -> Option<Action> {
 // switch with opponent pieces
 // do illegal moves
 
-struct KingDistance(Player, Player);
-struct BigPlays;
-struct Attacks;
-struct CountMoves;
-struct MoveAmount(Player);
-struct KingMoves(Player);
-struct QueenMoves;
-struct InCheck(Player);
+pub struct KingDistance(pub Player, pub Player);
+pub struct BigPlays;
+pub struct Attacks;
+pub struct CountMoves;
+pub struct MoveAmount(pub Player);
+pub struct KingMoves(pub Player);
+pub struct QueenMoves;
+pub struct InCheck(pub Player);
+// the usual piece values, the player's minus the opponent's
+pub struct Material(pub Player);
 
 impl EvalGame for KingDistance {
     fn eval(&self, _action: Action, game: &ChessGame) -> f32 {
@@ -413,10 +482,76 @@ impl EvalGame for InCheck {
     }
 }
 
-struct MaxChoose;
-struct MinChoose;
-struct FirstChoose;
-struct LastChoose;
+impl EvalGame for Material {
+    fn eval(&self, _action: Action, game: &ChessGame) -> f32 {
+        material(game, self.0)
+    }
+}
+
+// the usual piece values, the player's minus the opponent's
+pub fn material(game: &ChessGame, player: Player) -> f32 {
+    game.board.pieces()
+        .map(|p| match p.piece.owner() {
+            Some(owner) if owner == player => piece_value(p.piece),
+            Some(_) => -piece_value(p.piece),
+            None => 0.0,
+        })
+        .sum()
+}
+
+// combinations of evaluators, so a strategy can care about more than one thing
+
+// sum of the evaluators scaled by their weights
+pub struct Weighted<E>(pub Vec<(f32, E)>);
+// flip the preference of an evaluator
+pub struct Negate<E>(pub E);
+// 1 when the evaluator reaches the threshold, 0 otherwise
+pub struct Threshold<E>(pub E, pub f32);
+
+impl<E: EvalGame + ?Sized> EvalGame for Box<E> {
+    fn eval(&self, action: Action, game: &ChessGame) -> f32 {
+        self.as_ref().eval(action, game)
+    }
+
+    fn no_moves(&self) -> f32 {
+        self.as_ref().no_moves()
+    }
+}
+
+impl<E: EvalGame> EvalGame for Weighted<E> {
+    fn eval(&self, action: Action, game: &ChessGame) -> f32 {
+        self.0.iter().map(|(w, e)| w*e.eval(action, game)).sum()
+    }
+
+    fn no_moves(&self) -> f32 {
+        self.0.iter().map(|(w, e)| w*e.no_moves()).sum()
+    }
+}
+
+impl<E: EvalGame> EvalGame for Negate<E> {
+    fn eval(&self, action: Action, game: &ChessGame) -> f32 {
+        -self.0.eval(action, game)
+    }
+
+    fn no_moves(&self) -> f32 {
+        -self.0.no_moves()
+    }
+}
+
+impl<E: EvalGame> EvalGame for Threshold<E> {
+    fn eval(&self, action: Action, game: &ChessGame) -> f32 {
+        if self.0.eval(action, game) >= self.1 { 1.0 } else { 0.0 }
+    }
+
+    fn no_moves(&self) -> f32 {
+        if self.0.no_moves() >= self.1 { 1.0 } else { 0.0 }
+    }
+}
+
+pub struct MaxChoose;
+pub struct MinChoose;
+pub struct FirstChoose;
+pub struct LastChoose;
 
 impl ChooseMove for MaxChoose {
     fn choose_move(&self, left: (f32, Action), right: (f32, Action)) -> (f32, Action) {
@@ -450,14 +585,66 @@ impl ChooseMove for LastChoose {
     }
 }
 
-struct MaxCombine;
-struct MinCombine;
-struct AverageCombine;
-struct MedianCombine;
-struct VarianceCombine;
-struct SumCombine;
-struct ProductCombine;
-struct ConstantCombine(f32);
+// pick randomly, but better values are exponentially more likely
+// the higher the temperature the closer to uniformly random it gets
+pub struct SoftmaxChoose(pub f32);
+// same as softmax but lower values are better
+pub struct SoftminChoose(pub f32);
+// usually goes with the given chooser, but every so often picks any move
+pub struct EpsilonGreedy<C>(pub f32, pub C);
+
+impl ChooseMove for SoftmaxChoose {
+    fn choose_move(&self, left: (f32, Action), right: (f32, Action)) -> (f32, Action) {
+        self.choose(vec![left, right]).unwrap()
+    }
+
+    fn choose(&self, candidates: Vec<(f32, Action)>) -> Option<(f32, Action)> {
+        softmax(candidates, self.0, 1.0)
+    }
+}
+
+impl ChooseMove for SoftminChoose {
+    fn choose_move(&self, left: (f32, Action), right: (f32, Action)) -> (f32, Action) {
+        self.choose(vec![left, right]).unwrap()
+    }
+
+    fn choose(&self, candidates: Vec<(f32, Action)>) -> Option<(f32, Action)> {
+        softmax(candidates, self.0, -1.0)
+    }
+}
+
+impl<C: ChooseMove> ChooseMove for EpsilonGreedy<C> {
+    fn choose_move(&self, left: (f32, Action), right: (f32, Action)) -> (f32, Action) {
+        self.choose(vec![left, right]).unwrap()
+    }
+
+    fn choose(&self, candidates: Vec<(f32, Action)>) -> Option<(f32, Action)> {
+        if with_rng(|rng| rng.gen::<f32>()) < self.0 {
+            with_rng(|rng| candidates.choose(rng).copied())
+        } else {
+            self.1.choose(candidates)
+        }
+    }
+}
+
+impl<C: ChooseMove + ?Sized> ChooseMove for &C {
+    fn choose_move(&self, left: (f32, Action), right: (f32, Action)) -> (f32, Action) {
+        (**self).choose_move(left, right)
+    }
+
+    fn choose(&self, candidates: Vec<(f32, Action)>) -> Option<(f32, Action)> {
+        (**self).choose(candidates)
+    }
+}
+
+pub struct MaxCombine;
+pub struct MinCombine;
+pub struct AverageCombine;
+pub struct MedianCombine;
+pub struct VarianceCombine;
+pub struct SumCombine;
+pub struct ProductCombine;
+pub struct ConstantCombine(pub f32);
 
 impl CombineValues for MaxCombine {
     fn combine_values(&self, values: Vec<f32>) -> f32 {
@@ -512,7 +699,7 @@ impl CombineValues for ConstantCombine {
 
 // some utilities
 fn king(game: &ChessGame, player: Player) -> Option<PieceData> {
-    let kings = game.board.pieces_for(player).into_iter()
+    let kings = game.board.pieces_for(player)
         .filter(|x| matches!(x.piece, King(_,_)))
         .collect::<Vec<_>>();
     if kings.len() == 1 {
@@ -538,6 +725,30 @@ fn action_ids(action: Action) -> Vec<ID> {
     }
 }
 
+// sign flips between preferring high and low values
+// a temperature of 0 (or values that can't be weighted) just takes the best
+fn softmax(candidates: Vec<(f32, Action)>, temperature: f32, sign: f32) -> Option<(f32, Action)> {
+    let best = candidates.iter().map(|c| sign*c.0).fold(f32::NEG_INFINITY, f32::max);
+    let weights: Vec<f32> = candidates.iter()
+        .map(|c| ((sign*c.0 - best)/temperature).exp())
+        .collect();
+    match WeightedIndex::new(&weights) {
+        Ok(dist) if temperature > 0.0 => Some(candidates[with_rng(|rng| dist.sample(rng))]),
+        _ if sign > 0.0 => MaxChoose.choose(candidates),
+        _ => MinChoose.choose(candidates),
+    }
+}
+
+pub fn piece_value(piece: Piece) -> f32 {
+    match piece {
+        Pawn(_, _) => 1.0,
+        Knight(_, _) | Bishop(_, _) => 3.0,
+        Rook(_, _) => 5.0,
+        Queen(_, _) => 9.0,
+        Empty | King(_, _) => 0.0,
+    }
+}
+
 fn average(values: &[f32]) -> f32 {
     let mut count = 0.0;
     let mut sum = 0.0;
@@ -558,210 +769,3 @@ fn variance(values: &[f32]) -> f32 {
     }
     var/count
 }
-
-// dynamic dispatch version
-// not really sure which is "better"
-// at this point in time the enum/switch pattern and this version seem
-// pretty similar
-// other alternative is maybe a message passing style where there is a handler
-// and the "message" branches between name/description/run
-// trait Strategy {
-//   fn call(message: Message, res: &mut _) {
-//   }
-// }
-// enum Message {
-//   Name,
-//   Description,
-//   Run,
-// }
-// message passing seems unpleasant at first glance, but I could be wrong
-// 
-// the main performance bottleneck was the vector allocation, so the difference
-// in behaviors isn't obvious yet
-/*
-pub trait Strategy {
-    fn name() -> &'static str;
-    fn description() -> &'static str;
-    fn run(&self, game: &ChessGame) -> Option<Action>;
-}
-pub struct Random;
-pub struct Swarm;
-pub struct GiveUp;
-pub struct Huddle;
-pub struct SmellyKing;
-pub struct Intimidated;
-pub struct Ape;
-pub struct Sleepy;
-pub struct Pacifist;
-pub struct EqualOpportunity;
-pub struct Determined;
-pub struct Prepared;
-
-impl Strategy for Random {
-    fn name() -> &'static str {
-        "Random"
-    }
-
-    fn description() -> &'static str {
-        "Choose moves randomly."
-    }
-
-    fn run(&self, game: &ChessGame) -> Option<Action> {
-        let moves = game.possible_moves(game.turn);
-        if !moves.is_empty() {
-            Some(*moves.choose(&mut thread_rng()).unwrap())
-        } else {
-            None
-        }   
-    }
-}
-
-impl Strategy for Swarm {
-    fn name() -> &'static str {
-        "Swarm"
-    }
-
-    fn description() -> &'static str {
-        "Minimizes the total distance between all of their pieces and the enemy king."
-    }
-
-    fn run(&self, game: &ChessGame) -> Option<Action> {
-        let player = game.turn;
-        strategy(1, player, game, &KingDistance(player, player.toggle()), &MinChoose, &MinCombine)
-    }
-}
-
-impl Strategy for GiveUp {
-    fn name() -> &'static str {
-        "Give up"
-    }
-
-    fn description() -> &'static str {
-        "Chess isn't really their thing"
-    }
-
-    fn run(&self, game: &ChessGame) -> Option<Action> {
-        None
-    }
-}
-
-impl Strategy for Huddle {
-    fn name() -> &'static str {
-        "Huddle"
-    }
-
-    fn description() -> &'static str {
-        "Keep your pieces as close to your king as possible"
-    }
-
-    fn run(&self, game: &ChessGame) -> Option<Action> {
-        let player = game.turn;
-        strategy(1, player, game, &KingDistance(player, player), &MinChoose, &MinCombine)
-    }
-}
-
-impl Strategy for SmellyKing {
-    fn name() -> &'static str {
-        "Smelly King"
-    }
-    fn description() -> &'static str {
-        "Your king stinks, all your pieces run away"
-    }
-    fn run(&self, game: &ChessGame) -> Option<Action> {
-        let player = game.turn;
-        strategy(1, player, game, &KingDistance(player, player), &MaxChoose, &MaxCombine)
-    }
-}
-
-impl Strategy for Intimidated {
-    fn name() -> &'static str {
-        "Intimidated"
-    }
-    fn description() -> &'static str {
-        "The other king is quite scary, all your pieces run away from him"
-    }
-    fn run(&self, game: &ChessGame) -> Option<Action> {
-        let player = game.turn;
-        strategy(1, player, game, &KingDistance(player, player.toggle()), &MaxChoose, &MaxCombine)
-    }
-}
-
-impl Strategy for Ape {
-    fn name() -> &'static str {
-        "Ape"
-    }
-    fn description() -> &'static str {
-        "Big moves are the only way to get to the moon."
-    }
-    fn run(&self, game: &ChessGame) -> Option<Action> {
-        strategy(1, game.turn, game, &BigPlays, &MaxChoose, &MaxCombine)
-    }
-}
-
-impl Strategy for Sleepy {
-    fn name() -> &'static str {
-        "Sleepy"
-    }
-    fn description() -> &'static str {
-        "Too sleepy to move very far."
-    }
-    fn run(&self, game: &ChessGame) -> Option<Action> {
-        strategy(1, game.turn, game, &BigPlays, &MinChoose, &MinCombine)
-    }
-}
-
-impl Strategy for Pacifist {
-    fn name() -> &'static str {
-        "Pacifist"
-    }
-    fn description() -> &'static str {
-        "Minimizes the number of attacks it has on the opponent."
-    }
-    fn run(&self, game: &ChessGame) -> Option<Action> {
-        strategy(1, game.turn, game, &Attacks, &MinChoose, &SumCombine)
-    }
-}
-
-impl Strategy for Prepared {
-    fn name() -> &'static str {
-        "Prepared"
-    }
-    fn description() -> &'static str {
-        "Maximizes the number of attacks it has on the opponent."
-    }
-    fn run(&self, game: &ChessGame) -> Option<Action> {
-        strategy(1, game.turn, game, &Attacks, &MaxChoose, &SumCombine)
-    }
-}
-
-impl Strategy for EqualOpportunity {
-    fn name() -> &'static str {
-        "Equal Opportunity"
-    }
-    fn description() -> &'static str {
-        "Everyone needs a chance to succeed, keeps the move count for each piece relatively balanced."
-    }
-    fn run(&self, game: &ChessGame) -> Option<Action> {
-        strategy(1, game.turn, game, &CountMoves, &MinChoose, &MinCombine)
-    }
-}
-
-impl Strategy for Determined {
-    fn name() -> &'static str {
-        "Determined"
-    }
-    fn description() -> &'static str {
-        "Sticks to the choices that have already been made, the piece that has moved the most keeps getting chosen"
-    }
-    fn run(&self, game: &ChessGame) -> Option<Action> {
-        strategy(1, game.turn, game, &CountMoves, &MaxChoose, &MaxCombine)
-    }
-}
-
-pub fn strategy_map() -> HashMap<String, impl Strategy> {
-    let mut map = HashMap::new();
-    map.insert(Random::name().to_string(), Random);
-    
-    map
-}
-*/
@@ -0,0 +1,207 @@
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use shakmaty::{CastlingMode, Chess, Position, fen::Fen};
+use shakmaty_syzygy::{Dtz, Wdl};
+
+use crate::action::Action;
+use crate::adjudication::Adjudicate;
+use crate::bot::Bot;
+use crate::definition::Evaluator;
+use crate::final_state::{FinalState::{self, *}, GameResult, Reason};
+use crate::game::ChessGame;
+use crate::notation::{parse_uci, to_fen};
+use crate::trace::Trace;
+
+// syzygy endgame tables read from a local directory
+// once there are few enough pieces left the tables know the actual result of
+// the position and the moves that get there, so bots can stop flailing
+//
+// the tables follow the real fifty move rule (100 plies) while check_state
+// calls it a draw after 50 plies, so a long enough win can still get drawn
+
+#[derive(Debug)]
+pub enum TablebaseError {
+    Io(std::io::Error),
+    // the directory was readable but had no tables in it
+    NoTables(PathBuf),
+}
+
+impl Display for TablebaseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TablebaseError::Io(e) => write!(f, "could not read tablebase: {}", e),
+            TablebaseError::NoTables(path) => write!(f, "no syzygy tables in {}", path.display()),
+        }
+    }
+}
+
+impl std::error::Error for TablebaseError {}
+
+pub struct Tablebase {
+    tables: shakmaty_syzygy::Tablebase<Chess>,
+}
+
+impl Tablebase {
+    // no tables at all, so it never knows anything
+    pub fn new() -> Self {
+        Tablebase { tables: shakmaty_syzygy::Tablebase::new() }
+    }
+
+    pub fn open(dir: &Path) -> Result<Self, TablebaseError> {
+        let mut tablebase = Self::new();
+        let found = tablebase.tables.add_directory(dir).map_err(TablebaseError::Io)?;
+        if found == 0 {
+            return Err(TablebaseError::NoTables(dir.to_path_buf()));
+        }
+        Ok(tablebase)
+    }
+
+    pub fn max_pieces(&self) -> usize {
+        self.tables.max_pieces()
+    }
+
+    // counting is a lot cheaper than converting the whole game, so check
+    // before bothering with shakmaty
+    fn position(&self, game: &ChessGame) -> Option<Chess> {
+        if game.board.piece_count() > self.max_pieces() {
+            return None;
+        }
+        let fen: Fen = to_fen(game).parse().ok()?;
+        fen.into_position(CastlingMode::Standard).ok()
+    }
+
+    // how the game ends from here with perfect play, None when the tables don't
+    // cover the position or can't say for sure
+    // cursed wins and blessed losses are wins that the fifty move rule turns
+    // into draws
+    pub fn result(&self, game: &ChessGame) -> Option<FinalState> {
+        let position = self.position(game)?;
+        let wdl = self.tables.probe_wdl(&position).ok()?.unambiguous()?;
+        Some(match wdl {
+            Wdl::Win => Win(game.turn),
+            Wdl::Loss => Win(game.turn.toggle()),
+            _ => Draw,
+        })
+    }
+
+    // the fastest way to the best result there is
+    pub fn best_move(&self, game: &ChessGame) -> Option<Action> {
+        let position = self.position(game)?;
+        let (m, _) = self.tables.best_move(&position).ok()??;
+        parse_uci(game, &m.to_uci(CastlingMode::Standard).to_string())
+    }
+
+    // the move that does the opponent the most good: throw a won game, and
+    // lose as quickly as possible once it is lost
+    pub fn worst_move(&self, game: &ChessGame) -> Option<Action> {
+        let position = self.position(game)?;
+        let mut worst = None;
+        for m in position.legal_moves() {
+            let mut after = position.clone();
+            after.play_unchecked(&m);
+            let Some(score) = self.opponent_score(&after) else {
+                continue;
+            };
+            if worst.as_ref().is_none_or(|(s, _)| score > *s) {
+                worst = Some((score, m));
+            }
+        }
+        let (_, m) = worst?;
+        parse_uci(game, &m.to_uci(CastlingMode::Standard).to_string())
+    }
+
+    // how well things are going for whoever is to move, bigger is better
+    // within the same result winning sooner and losing later are better, which
+    // comes out as the negated distance to zeroing either way
+    fn opponent_score(&self, position: &Chess) -> Option<(Wdl, i32)> {
+        if position.is_checkmate() {
+            return Some((Wdl::Loss, 0));
+        }
+        if position.is_stalemate() || position.is_insufficient_material() {
+            return Some((Wdl::Draw, 0));
+        }
+        let Dtz(dtz) = self.tables.probe_dtz(position).ok()?.ignore_rounding();
+        Some((Wdl::from_dtz(Dtz(dtz)), -dtz))
+    }
+}
+
+impl Default for Tablebase {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// shared so the bots can use the same tables
+impl Adjudicate for Arc<Tablebase> {
+    fn adjudicate(&mut self, game: &ChessGame) -> Option<GameResult> {
+        self.result(game).map(|state| GameResult::new(state, Reason::Tablebase))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endgame {
+    // play the tables' best move
+    Best,
+    // play the move that helps the opponent the most
+    Worst,
+}
+
+// lets a bot play normally until the tables take over
+pub struct Tablebased {
+    bot: Box<dyn Bot>,
+    tables: Arc<Tablebase>,
+    endgame: Endgame,
+}
+
+impl Tablebased {
+    pub fn new(bot: Box<dyn Bot>, tables: Arc<Tablebase>, endgame: Endgame) -> Self {
+        Tablebased { bot, tables, endgame }
+    }
+
+    fn table_move(&self, game: &ChessGame) -> Option<Action> {
+        match self.endgame {
+            Endgame::Best => self.tables.best_move(game),
+            Endgame::Worst => self.tables.worst_move(game),
+        }
+    }
+}
+
+impl Bot for Tablebased {
+    fn name(&self) -> &str {
+        self.bot.name()
+    }
+
+    fn description(&self) -> &str {
+        self.bot.description()
+    }
+
+    fn choose(&mut self, game: &ChessGame) -> Option<Action> {
+        self.table_move(game).or_else(|| self.bot.choose(game))
+    }
+
+    fn new_game(&mut self) {
+        self.bot.new_game()
+    }
+
+    fn model(&self) -> Option<Box<dyn Bot>> {
+        Some(Box::new(Tablebased::new(self.bot.model()?, self.tables.clone(), self.endgame)))
+    }
+
+    fn meet_opponent(&mut self, opponent: &dyn Bot) {
+        self.bot.meet_opponent(opponent)
+    }
+
+    // the tables' moves aren't worked out, so there is nothing to show for them
+    fn trace(&mut self, game: &ChessGame) -> Option<Trace> {
+        if self.table_move(game).is_some() {
+            return None;
+        }
+        self.bot.trace(game)
+    }
+
+    fn evaluator(&self) -> Option<Evaluator> {
+        self.bot.evaluator()
+    }
+}
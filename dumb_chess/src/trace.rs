@@ -0,0 +1,168 @@
+use std::fmt::Display;
+
+use serde::{Serialize, Serializer};
+
+use crate::action::Action;
+use crate::notation::uci;
+use crate::player::Player;
+
+// everything that went into a single decision, for working out why a bot
+// played something weird
+// made by the traced versions of the strategy functions, which go through the
+// moves in the same (shuffled) order, so with the same seed they pick the same
+// move as the untraced ones
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Node {
+    #[serde(serialize_with = "as_uci")]
+    pub action: Action,
+    pub san: String,
+    // the evaluation at the bottom, otherwise what the combiner made of the
+    // children (or no_moves when there weren't any)
+    pub value: f32,
+    // the same player's next moves, the opponent doesn't get a turn in the lookahead
+    pub children: Vec<Node>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StageTrace {
+    pub depth: u8,
+    // in the order they were looked at, which is what breaks ties
+    pub candidates: Vec<Node>,
+    // what the chooser picked
+    #[serde(serialize_with = "as_uci_option")]
+    pub chosen: Option<Action>,
+    pub value: Option<f32>,
+    // every candidate with the same value as the chosen one
+    #[serde(serialize_with = "as_uci_list")]
+    pub tied: Vec<Action>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Trace {
+    pub player: Player,
+    pub fen: String,
+    // just the one for a normal strategy, more for tie breaks
+    pub stages: Vec<StageTrace>,
+    // the move actually played
+    #[serde(serialize_with = "as_uci_option")]
+    pub chosen: Option<Action>,
+}
+
+impl Node {
+    pub fn new(action: Action, san: String, value: f32, children: Vec<Node>) -> Self {
+        Node { action, san, value, children }
+    }
+}
+
+impl StageTrace {
+    pub fn new(depth: u8, candidates: Vec<Node>, chosen: Option<(f32, Action)>) -> Self {
+        // same as the lexicographic strategy, the chosen move counts even if
+        // its value can't be compared (NaN)
+        let tied = chosen.map_or(Vec::new(), |(value, action)| {
+            candidates.iter()
+                .filter(|c| c.value == value || c.action == action)
+                .map(|c| c.action)
+                .collect()
+        });
+        StageTrace { depth, candidates, chosen: chosen.map(|c| c.1), value: chosen.map(|c| c.0), tied }
+    }
+
+    fn san(&self, action: Action) -> &str {
+        self.candidates.iter().find(|c| c.action == action).map_or("?", |c| &c.san)
+    }
+}
+
+impl Trace {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    // graphviz, the chosen move is filled in and the ones tied with it are bold
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph trace {\n    node [shape=box, fontname=\"monospace\"];\n");
+        dot.push_str(&format!("    root [label=\"{:?} to move\\n{}\"];\n", self.player, self.fen));
+        let mut next = 0;
+        for (i, stage) in self.stages.iter().enumerate() {
+            let parent = if self.stages.len() == 1 {
+                "root".to_string()
+            } else {
+                let id = format!("stage{}", i);
+                dot.push_str(&format!("    {} [label=\"stage {} (depth {})\", shape=ellipse];\n", id, i + 1, stage.depth));
+                dot.push_str(&format!("    root -> {};\n", id));
+                id
+            };
+            for c in &stage.candidates {
+                let style = if Some(c.action) == stage.chosen {
+                    ", style=filled, fillcolor=lightblue"
+                } else if stage.tied.contains(&c.action) {
+                    ", style=bold"
+                } else {
+                    ""
+                };
+                dot_node(&mut dot, &parent, c, style, &mut next);
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn dot_node(dot: &mut String, parent: &str, node: &Node, style: &str, next: &mut usize) {
+    let id = format!("n{}", next);
+    *next += 1;
+    dot.push_str(&format!("    {} [label=\"{}\\n{}\"{}];\n", id, node.san, node.value, style));
+    dot.push_str(&format!("    {} -> {};\n", parent, id));
+    for child in &node.children {
+        dot_node(dot, &id, child, "", next);
+    }
+}
+
+impl Display for Trace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{:?} to move in {}", self.player, self.fen)?;
+        for (i, stage) in self.stages.iter().enumerate() {
+            writeln!(f)?;
+            writeln!(f, "Stage {} looking {} deep, in the order they were tried:", i + 1, stage.depth)?;
+            for c in &stage.candidates {
+                let mark = if Some(c.action) == stage.chosen {
+                    "<- chosen"
+                } else if stage.tied.contains(&c.action) {
+                    "tied"
+                } else {
+                    ""
+                };
+                let from = if c.children.is_empty() {
+                    String::new()
+                } else {
+                    format!("from {} replies", c.children.len())
+                };
+                writeln!(f, "  {:<8} {:>10} {:<18} {}", c.san, c.value, from, mark)?;
+            }
+            if stage.tied.len() > 1 {
+                let tied: Vec<_> = stage.tied.iter().map(|a| stage.san(*a)).collect();
+                writeln!(f, "{} moves tied ({}), the order they were tried in settles it", tied.len(), tied.join(", "))?;
+            }
+        }
+        writeln!(f)?;
+        match self.chosen.and_then(|a| self.stages.iter().map(|s| s.san(a)).find(|s| *s != "?")) {
+            Some(san) => writeln!(f, "Plays {}", san),
+            None => writeln!(f, "Gives up"),
+        }
+    }
+}
+
+fn as_uci<S: Serializer>(action: &Action, s: S) -> Result<S::Ok, S::Error> {
+    s.serialize_str(&uci(*action))
+}
+
+fn as_uci_option<S: Serializer>(action: &Option<Action>, s: S) -> Result<S::Ok, S::Error> {
+    match action {
+        Some(a) => s.serialize_some(&uci(*a)),
+        None => s.serialize_none(),
+    }
+}
+
+fn as_uci_list<S: Serializer>(actions: &[Action], s: S) -> Result<S::Ok, S::Error> {
+    s.collect_seq(actions.iter().map(|a| uci(*a)))
+}
@@ -0,0 +1,194 @@
+use std::io;
+use std::time::{Duration, Instant};
+
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Paragraph, Wrap},
+    DefaultTerminal, Frame,
+};
+
+use crate::adjudication::Adjudicate;
+use crate::bot::Bot;
+use crate::coord::Coord;
+use crate::final_state::GameResult;
+use crate::game::{ChessGame, Match, Ply};
+use crate::notation::{move_squares, san};
+use crate::piece::Piece::{self, *};
+use crate::player::Player::*;
+
+// a full screen view of a game between two bots, instead of printing the
+// board over and over
+// the game is only moved along by advance, so everything but play can be
+// driven (and drawn to a test backend) without a terminal
+
+// the delays to pick from with + and -, in milliseconds
+const DELAYS: [u64; 8] = [0, 50, 100, 250, 500, 1000, 2000, 5000];
+
+pub struct Viewer<'a> {
+    pub game: Match<'a>,
+    // the moves so far in san
+    pub sans: Vec<String>,
+    pub paused: bool,
+    // index into DELAYS
+    speed: usize,
+    pub quit: bool,
+    last: Option<(Coord, Coord)>,
+}
+
+impl<'a> Viewer<'a> {
+    pub fn new(game: Match<'a>) -> Self {
+        Viewer { game, sans: Vec::new(), paused: false, speed: 4, quit: false, last: None }
+    }
+
+    pub fn delay(&self) -> Duration {
+        Duration::from_millis(DELAYS[self.speed])
+    }
+
+    pub fn over(&self) -> Option<GameResult> {
+        self.game.result
+    }
+
+    // one ply, nothing happens once the game is over
+    pub fn advance(&mut self) {
+        if self.over().is_some() {
+            return;
+        }
+        let before = self.game.game.clone();
+        if let Ply::Moved(act) = self.game.play() {
+            self.sans.push(san(&before, act));
+            let (from, to, _) = move_squares(act);
+            self.last = Some((from, to));
+        }
+    }
+
+    pub fn key(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            KeyCode::Char(' ') | KeyCode::Char('p') => self.paused = !self.paused,
+            // a single move, and stay paused after it
+            KeyCode::Char('s') | KeyCode::Right => {
+                self.paused = true;
+                self.advance();
+            }
+            KeyCode::Char('+') | KeyCode::Char('=') | KeyCode::Up => self.speed = self.speed.saturating_sub(1),
+            KeyCode::Char('-') | KeyCode::Down => self.speed = (self.speed + 1).min(DELAYS.len() - 1),
+            _ => {}
+        }
+    }
+
+    pub fn draw(&self, frame: &mut Frame) {
+        let [left, right] = Layout::horizontal([Constraint::Length(28), Constraint::Min(24)]).areas(frame.area());
+        let [board, status] = Layout::vertical([Constraint::Length(11), Constraint::Min(4)]).areas(left);
+        let [players, moves] = Layout::vertical([Constraint::Length(8), Constraint::Min(3)]).areas(right);
+        frame.render_widget(self.board(), board);
+        frame.render_widget(self.status(), status);
+        frame.render_widget(self.players(), players);
+        frame.render_widget(self.moves(moves), moves);
+    }
+
+    fn board(&self) -> Paragraph<'static> {
+        let game = &self.game.game;
+        // row 0 is the 8th rank, so it goes at the top
+        let mut lines: Vec<Line> = (0..8).map(|row| {
+            let mut spans = vec![Span::raw(format!("{} ", 8 - row))];
+            for col in 0..8 {
+                let at = Coord::new(row, col);
+                let mut background = if (row + col) % 2 == 0 { Color::Rgb(240, 217, 181) } else { Color::Rgb(181, 136, 99) };
+                if self.last.is_some_and(|(from, to)| at == from || at == to) {
+                    background = Color::Rgb(205, 210, 106);
+                }
+                let piece = game.board.get(at);
+                let foreground = if piece.owner() == Some(White) { Color::White } else { Color::Black };
+                spans.push(Span::styled(format!(" {} ", glyph(piece)), Style::default().fg(foreground).bg(background)));
+            }
+            Line::from(spans)
+        }).collect();
+        lines.push(Line::from("   a  b  c  d  e  f  g  h"));
+        Paragraph::new(lines).block(Block::bordered().title("Board"))
+    }
+
+    fn status(&self) -> Paragraph<'static> {
+        let state = match self.over() {
+            Some(result) => format!("Final state: {:?} ({:?})", result.state, result.reason),
+            None if self.paused => format!("{:?} to move, paused", self.game.game.turn),
+            None => format!("{:?} to move, {} ms between moves", self.game.game.turn, self.delay().as_millis()),
+        };
+        let lines = vec![
+            Line::styled(state, Style::default().add_modifier(Modifier::BOLD)),
+            Line::from("space pause, s step, +/- speed, q quit"),
+        ];
+        Paragraph::new(lines).wrap(Wrap { trim: true }).block(Block::bordered().title("Status"))
+    }
+
+    fn players(&self) -> Paragraph<'static> {
+        let turn = self.game.game.turn;
+        let mut lines = Vec::new();
+        for player in [White, Black] {
+            let bot = self.game.player(player);
+            let marker = if self.over().is_none() && player == turn { "> " } else { "  " };
+            lines.push(Line::styled(format!("{}{:?}: {}", marker, player, bot.name()), Style::default().add_modifier(Modifier::BOLD)));
+            lines.push(Line::from(format!("  {}", bot.description())));
+        }
+        Paragraph::new(lines).wrap(Wrap { trim: false }).block(Block::bordered().title("Players"))
+    }
+
+    // two plies to a line, scrolled so the latest is showing
+    fn moves(&self, area: Rect) -> Paragraph<'static> {
+        let lines: Vec<Line> = self.sans.chunks(2).enumerate()
+            .map(|(i, pair)| Line::from(format!("{:>3}. {:<8} {}", i + 1, pair[0], pair.get(1).map_or("", |s| s.as_str()))))
+            .collect();
+        let height = area.height.saturating_sub(2) as usize;
+        let skip = lines.len().saturating_sub(height);
+        Paragraph::new(lines).scroll((skip as u16, 0)).block(Block::bordered().title("Moves"))
+    }
+}
+
+fn glyph(piece: Piece) -> &'static str {
+    match piece {
+        Empty          => " ",
+        Pawn(_, _)     => "♟",
+        Knight(_, _)   => "♞",
+        Bishop(_, _)   => "♝",
+        Rook(_, _)     => "♜",
+        Queen(_, _)    => "♛",
+        King(_, _)     => "♚",
+    }
+}
+
+// plays the game on the whole terminal until it's finished and then waits to
+// be quit, None if it was quit before the end
+pub fn play(black_player: &mut dyn Bot, white_player: &mut dyn Bot, judge: &mut dyn Adjudicate) -> io::Result<(ChessGame, Option<GameResult>)> {
+    let mut terminal = ratatui::init();
+    let mut viewer = Viewer::new(Match::new(black_player, white_player, judge));
+    let result = watch(&mut terminal, &mut viewer);
+    ratatui::restore();
+    result?;
+    Ok((viewer.game.game.clone(), viewer.over()))
+}
+
+fn watch(terminal: &mut DefaultTerminal, viewer: &mut Viewer) -> io::Result<()> {
+    let mut last_move = Instant::now();
+    while !viewer.quit {
+        terminal.draw(|frame| viewer.draw(frame))?;
+        let waiting = viewer.paused || viewer.over().is_some();
+        let timeout = if waiting {
+            Duration::from_millis(250)
+        } else {
+            viewer.delay().saturating_sub(last_move.elapsed())
+        };
+        if event::poll(timeout)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    viewer.key(key.code);
+                }
+            }
+        } else if !waiting {
+            viewer.advance();
+            last_move = Instant::now();
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use dumb_chess::adjudication::{Adjudicate, Adjudicator, DrawRule, ResignRule, Rules};
+use dumb_chess::final_state::{FinalState, GameResult, Reason};
+use dumb_chess::game::{ChessGame, play_game_adjudicated};
+use dumb_chess::notation::parse_uci;
+use dumb_chess::player::Player::*;
+use dumb_chess::strategy::Strategy;
+
+fn play(moves: &[&str]) -> Vec<ChessGame> {
+    let mut games = vec![ChessGame::new()];
+    for m in moves {
+        let game = games.last().unwrap();
+        let act = parse_uci(game, m).unwrap_or_else(|| panic!("{} isn't legal", m));
+        games.push(game.step(act));
+    }
+    games
+}
+
+#[test]
+fn max_plies() {
+    let rules = Rules { max_plies: Some(10), ..Rules::default() };
+    let mut judge = Adjudicator::new(rules);
+    let (game, result) = play_game_adjudicated(&mut Strategy::Random, &mut Strategy::Sleepy, &mut judge);
+    // sleepy only ever moves a knight back and forth, so nothing ends it sooner
+    assert_eq!(game.moves.len(), 10);
+    assert_eq!(result, GameResult::new(FinalState::Draw, Reason::MaxPlies));
+    assert!(result.reason.adjudicated());
+}
+
+#[test]
+fn quiet_draw() {
+    let rules = Rules { draw: Some(DrawRule { after: 2, plies: 3, margin: 0.0 }), ..Rules::default() };
+    let mut judge = Adjudicator::new(rules);
+    let games = play(&["g1f3", "g8f6", "f3g1", "f6g8"]);
+    let results: Vec<_> = games.iter().map(|g| judge.adjudicate(g)).collect();
+    // counting starts at ply 2, so it takes until ply 4
+    assert_eq!(results[..4], [None, None, None, None]);
+    assert_eq!(results[4], Some(GameResult::new(FinalState::Draw, Reason::QuietDraw)));
+}
+
+#[test]
+fn resignation() {
+    let rules = Rules { resign: Some(ResignRule { plies: 3, margin: 3.0 }), ..Rules::default() };
+    let mut judge = Adjudicator::new(rules);
+    // black gives the queen away and white takes it
+    let games = play(&["e2e4", "e7e5", "d1h5", "d8g5", "h5g5", "a7a6", "a2a3"]);
+    let results: Vec<_> = games.iter().map(|g| judge.adjudicate(g)).collect();
+    assert!(results[..7].iter().all(|r| r.is_none()));
+    assert_eq!(results[7], Some(GameResult::new(FinalState::Win(White), Reason::Resignation)));
+
+    // counting starts over each game
+    judge.new_game();
+    assert_eq!(judge.adjudicate(&games[5]), None);
+}
@@ -0,0 +1,99 @@
+use dumb_chess::analysis::{choices, evaluators, score_moves};
+use dumb_chess::zobrist::polyglot_key;
+use dumb_chess::final_state::FinalState::Draw;
+use dumb_chess::game::ChessGame;
+use dumb_chess::notation::{from_fen, parse_uci, to_fen, ParseFenError};
+use dumb_chess::player::Player::*;
+use dumb_chess::registry::Registry;
+use dumb_chess::rng;
+
+#[test]
+fn fen_round_trip() {
+    for fen in [
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+        "r3k2r/ppp2ppp/8/3pP3/8/8/PPP2PPP/R3K2R w Kq d6 0 10",
+        "4k3/8/8/8/8/8/8/4K2R w K - 7 42",
+        "8/8/8/4k3/8/8/8/4K3 b - - 12 60",
+    ] {
+        let game = from_fen(fen).unwrap();
+        assert_eq!(to_fen(&game), fen);
+    }
+}
+
+#[test]
+fn fen_matches_played_game() {
+    let mut game = ChessGame::new();
+    for m in ["e2e4", "c7c5", "g1f3", "d7d6"] {
+        game = game.step(parse_uci(&game, m).unwrap());
+    }
+    let loaded = from_fen(&to_fen(&game)).unwrap();
+    assert_eq!(loaded.turn, White);
+    assert_eq!(polyglot_key(&loaded), polyglot_key(&game));
+    assert_eq!(loaded.possible_moves(White).count(), game.possible_moves(White).count());
+}
+
+#[test]
+fn fen_castling_and_en_passant() {
+    let game = from_fen("r3k2r/ppp2ppp/8/3pP3/8/8/PPP2PPP/R3K2R w Kq d6 0 10").unwrap();
+    assert!(parse_uci(&game, "e1g1").is_some());
+    assert!(parse_uci(&game, "e1c1").is_none());
+    assert!(parse_uci(&game, "e5d6").is_some());
+
+    let game = game.step(parse_uci(&game, "h2h3").unwrap());
+    assert!(parse_uci(&game, "e8c8").is_some());
+    assert!(parse_uci(&game, "e8g8").is_none());
+
+    assert!(from_fen("not a fen").is_err());
+}
+
+#[test]
+fn fen_games_start_with_no_moves() {
+    let game = from_fen("r3k2r/ppp2ppp/8/3pP3/8/8/PPP2PPP/R3K2R w Kq d6 49 10").unwrap();
+    assert!(game.moves.is_empty());
+    assert_eq!(game.halfmoves(), 49);
+    assert!(game.last_move().is_some());
+    // one more quiet move and it's a draw
+    let game = game.step(parse_uci(&game, "e1f1").unwrap());
+    assert_eq!(game.moves.len(), 1);
+    assert_eq!(game.check_state(), Some(Draw));
+}
+
+#[test]
+fn bad_fens() {
+    assert_eq!(from_fen("8/8/8/8/8/8/8/8 w").err(), Some(ParseFenError::Missing("castling")));
+    assert_eq!(from_fen("8/8/8/8/8/8/8/9 w - - 0 1").err(), Some(ParseFenError::Invalid("board", "8/8/8/8/8/8/8/9".to_string())));
+    assert!(from_fen("8/8/8/8/8/8/8/7x w - - 0 1").is_err());
+    assert!(from_fen("8/8/8/8/8/8/8 w - - 0 1").is_err());
+    assert!(from_fen("8/8/8/8/8/8/8/8 x - - 0 1").is_err());
+    assert!(from_fen("8/8/8/8/8/8/8/8 w KX - 0 1").is_err());
+    assert!(from_fen("8/8/8/8/8/8/8/8 w - e9 0 1").is_err());
+    assert!(from_fen("8/8/8/8/8/8/8/8 w - - x 1").is_err());
+    // only the squares a pawn can skip over
+    assert_eq!(from_fen("4k3/8/8/8/8/8/8/4K3 b - h8 0 1").err(), Some(ParseFenError::Invalid("en passant square", "h8".to_string())));
+    assert!(from_fen("4k3/8/8/8/8/8/8/4K3 w - a1 0 1").is_err());
+    assert!(from_fen("4k3/8/8/8/4P3/8/8/4K3 w - e3 0 1").is_err());
+    assert!(from_fen("4k3/8/8/8/4P3/8/8/4K3 b - e3 0 1").is_ok());
+    // the clocks can be left off
+    assert_eq!(to_fen(&from_fen("4k3/8/8/8/8/8/8/4K3 w - -").unwrap()), "4k3/8/8/8/8/8/8/4K3 w - - 0 1");
+}
+
+#[test]
+fn scores_every_move() {
+    let game = ChessGame::new();
+    let moves = score_moves(&game);
+    assert_eq!(moves.len(), 20);
+    assert!(moves.iter().all(|m| m.scores.len() == evaluators().len()));
+
+    // taking the queen is worth the most material
+    let game = from_fen("4k3/8/8/3q4/4P3/8/8/4K3 w - - 0 1").unwrap();
+    let material = evaluators().iter().position(|(name, _)| *name == "Material").unwrap();
+    let best = score_moves(&game).into_iter().max_by(|a, b| a.scores[material].total_cmp(&b.scores[material])).unwrap();
+    assert_eq!(best.san, "exd5");
+
+    rng::seed(3);
+    let registry = Registry::new();
+    let picks = choices(&ChessGame::new(), &registry);
+    assert_eq!(picks.len(), registry.entries().count());
+    assert!(picks.iter().any(|(_, choice)| choice.is_some()));
+}
@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+use dumb_chess::archive::{self, Archive, ArchiveError, ArchivedGame, Outcome, Query};
+use dumb_chess::final_state::{FinalState, GameResult, Reason};
+use dumb_chess::game::{ChessGame, play_game};
+use dumb_chess::notation::{parse_uci, san, uci};
+use dumb_chess::player::Player::*;
+use dumb_chess::rng;
+use dumb_chess::strategy::Strategy;
+use shakmaty::{Position, san::SanPlus, uci::UciMove};
+
+fn archive_path(name: &str) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("dumb_chess_{}_{}.jsonl", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn game(white: &str, black: &str, moves: &[&str], state: FinalState, reason: Reason) -> ArchivedGame {
+    let mut g = ChessGame::new();
+    let mut actions = Vec::new();
+    for m in moves {
+        let act = parse_uci(&g, m).unwrap();
+        g = g.step(act);
+        actions.push(act);
+    }
+    ArchivedGame::new(white, black, 1, &actions, GameResult::new(state, reason), Duration::from_millis(5))
+}
+
+#[test]
+fn san_matches_shakmaty() {
+    // lots of random games cover the odd cases, castling, en passant and
+    // promotions with and without a take
+    for seed in 0..20 {
+        rng::seed(seed);
+        let (end, _) = play_game(&mut Strategy::Random, &mut Strategy::Random);
+        let mut game = ChessGame::new();
+        let mut position = shakmaty::Chess::default();
+        for act in end.moves {
+            let m = uci(act).parse::<UciMove>().unwrap().to_move(&position).unwrap();
+            assert_eq!(san(&game, act), SanPlus::from_move(position.clone(), &m).to_string());
+            position.play_unchecked(&m);
+            game = game.step(act);
+        }
+    }
+}
+
+#[test]
+fn append_and_read_back() {
+    let path = archive_path("append");
+    let archive = Archive::new(&path);
+    let first = game("Ape", "Swarm", &["f2f3", "e7e5", "g2g4", "d8h4"], FinalState::Win(Black), Reason::Checkmate);
+    let second = game("Swarm", "Ape", &["e2e4"], FinalState::Draw, Reason::MaxPlies);
+    archive.append(&first).unwrap();
+    archive.append(&second).unwrap();
+    assert_eq!(archive.games().unwrap(), vec![first.clone(), second]);
+    assert_eq!(first.plies, 4);
+    assert_eq!(first.actions().unwrap().len(), 4);
+    let record = first.record().unwrap();
+    assert_eq!(ArchivedGame { played_at: first.played_at, ..ArchivedGame::from(&record) }, first);
+
+    std::fs::write(&path, "{\"white\": \"Ape\"}\n").unwrap();
+    assert!(matches!(archive.games(), Err(ArchiveError::Json(1, _))));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn queries() {
+    let games = [
+        game("Ape", "Swarm", &["e2e4"], FinalState::Win(Black), Reason::Checkmate),
+        game("Swarm", "Ape", &["e2e4"], FinalState::Win(White), Reason::Resignation),
+        game("Ape", "Random", &["e2e4"], FinalState::Win(White), Reason::Checkmate),
+        game("Random", "Swarm", &["e2e4"], FinalState::Draw, Reason::FiftyMoves),
+    ];
+    let found = |query: Query| games.iter().enumerate().filter(|(_, g)| query.matches(g)).map(|(i, _)| i).collect::<Vec<_>>();
+
+    // all of ape's losses as white
+    assert_eq!(found(Query { player: Some("Ape".to_string()), color: Some(White), outcome: Some(Outcome::Loss), ..Query::default() }), vec![0]);
+    assert_eq!(found(Query { player: Some("Ape".to_string()), outcome: Some(Outcome::Loss), ..Query::default() }), vec![0, 1]);
+    assert_eq!(found(Query { player: Some("Swarm".to_string()), ..Query::default() }), vec![0, 1, 3]);
+    assert_eq!(found(Query { reason: Some(Reason::Checkmate), ..Query::default() }), vec![0, 2]);
+    assert_eq!(found(Query { outcome: Some(Outcome::Draw), ..Query::default() }), vec![3]);
+    assert_eq!(found(Query::default()).len(), 4);
+}
+
+#[test]
+fn pgn_export() {
+    let g = game("Ape", "Swarm", &["f2f3", "e7e5", "g2g4", "d8h4"], FinalState::Win(Black), Reason::Checkmate);
+    let pgn = archive::to_pgn(&g).unwrap();
+    assert!(pgn.contains("[White \"Ape\"]\n[Black \"Swarm\"]\n[Result \"0-1\"]\n"));
+    assert!(pgn.ends_with("\n1. f3 e5 2. g4 Qh4# 0-1\n\n"));
+
+    let mut broken = g.clone();
+    broken.moves.push("a1a8".to_string());
+    assert!(archive::to_pgn(&broken).is_none());
+}
@@ -0,0 +1,75 @@
+use dumb_chess::adjudication::{Adjudicate, Adjudicator, Rules};
+use dumb_chess::batch::{self, Batch, BatchError};
+use dumb_chess::registry::Registry;
+
+fn judge() -> Box<dyn Adjudicate> {
+    Box::new(Adjudicator::new(Rules { max_plies: Some(40), ..Rules::default() }))
+}
+
+fn players() -> Vec<String> {
+    ["Random", "Swarm", "Sleepy"].iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn round_robin_schedule() {
+    let games = batch::round_robin(&players(), 2, 1);
+    // 3 players, everyone plays everyone else as each color, twice
+    assert_eq!(games.len(), 12);
+    assert!(games.iter().all(|g| g.white != g.black));
+    let mut seeds: Vec<_> = games.iter().map(|g| g.seed).collect();
+    seeds.sort();
+    seeds.dedup();
+    assert_eq!(seeds.len(), 12);
+    assert_eq!(games, batch::round_robin(&players(), 2, 1));
+}
+
+#[test]
+fn threads_dont_change_results() {
+    let registry = Registry::new();
+    let games = batch::round_robin(&players(), 1, 3);
+    let mut runner = Batch::new(|name| registry.create(name), judge);
+
+    runner.threads = 1;
+    let mut seen = 0;
+    let one = runner.run(&games, |done, _| seen = done).unwrap();
+    assert_eq!(seen, games.len());
+
+    runner.threads = 4;
+    let many = runner.run(&games, |_, _| {}).unwrap();
+    let summary = |records: &[batch::GameRecord]| records.iter()
+        .map(|r| (r.white.clone(), r.black.clone(), r.seed, r.result, r.moves.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(summary(&one), summary(&many));
+
+    let standings = batch::standings(&one);
+    assert_eq!(standings.len(), 3);
+    assert!(standings.iter().all(|(_, t)| t.games() == 4));
+    let table = batch::cross_table(&one);
+    assert_eq!(table.len(), 6);
+    assert!(standings.windows(2).all(|w| w[0].1.score() >= w[1].1.score()));
+}
+
+#[test]
+fn unknown_strategy() {
+    let registry = Registry::new();
+    let games = batch::round_robin(&["Random".to_string(), "Nobody".to_string()], 1, 0);
+    let runner = Batch::new(|name| registry.create(name), judge);
+    assert!(matches!(runner.run(&games, |_, _| {}), Err(BatchError::UnknownStrategy(name)) if name == "Nobody"));
+}
+
+#[test]
+fn ratings_follow_scores() {
+    let registry = Registry::new();
+    let games = batch::round_robin(&players(), 2, 5);
+    let records = Batch::new(|name| registry.create(name), judge).run(&games, |_, _| {}).unwrap();
+    let ratings = batch::ratings(&records);
+    assert_eq!(ratings.len(), 3);
+    let total: f64 = ratings.iter().map(|(_, r)| r).sum();
+    assert!(total.abs() < 1e-6);
+    // everyone played everyone equally often, so the order is the same as the scores
+    let scores: Vec<f32> = batch::standings(&records).into_iter().map(|(_, t)| t.score()).collect();
+    let order: Vec<f32> = ratings.iter()
+        .map(|(name, _)| batch::standings(&records).into_iter().find(|(n, _)| n == name).unwrap().1.score())
+        .collect();
+    assert_eq!(order, scores);
+}
@@ -0,0 +1,109 @@
+use std::sync::Arc;
+
+use dumb_chess::action::Action::*;
+use dumb_chess::book::{Book, BookError, Booked};
+use dumb_chess::zobrist::polyglot_key;
+use dumb_chess::bot::Bot;
+use dumb_chess::game::ChessGame;
+use dumb_chess::notation::{parse_uci, to_fen, uci};
+use dumb_chess::strategy::Strategy;
+
+fn play(moves: &[&str]) -> ChessGame {
+    let mut game = ChessGame::new();
+    for m in moves {
+        let act = parse_uci(&game, m).unwrap_or_else(|| panic!("{} isn't legal", m));
+        game = game.step(act);
+    }
+    game
+}
+
+fn entry(key: u64, raw_move: u16, weight: u16) -> Vec<u8> {
+    let mut bytes = key.to_be_bytes().to_vec();
+    bytes.extend(raw_move.to_be_bytes());
+    bytes.extend(weight.to_be_bytes());
+    bytes.extend(0u32.to_be_bytes());
+    bytes
+}
+
+const START: u64 = 0x463b96181691fc9c;
+const E2E4: u16 = 4 | (3 << 3) | (4 << 6) | (1 << 9);
+const D2D4: u16 = 3 | (3 << 3) | (3 << 6) | (1 << 9);
+
+#[test]
+fn start_fen() {
+    assert_eq!(to_fen(&ChessGame::new()), "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+    assert_eq!(to_fen(&play(&["e2e4"])), "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1");
+    assert_eq!(to_fen(&play(&["g1f3", "g8f6", "h1g1"])), "rnbqkb1r/pppppppp/5n2/8/8/5N2/PPPPPPPP/RNBQKBR1 b Qkq - 3 2");
+}
+
+#[test]
+fn polyglot_keys() {
+    // the examples from the polyglot spec
+    assert_eq!(polyglot_key(&ChessGame::new()), START);
+    assert_eq!(polyglot_key(&play(&["e2e4"])), 0x823c9b50fd114196);
+    assert_eq!(polyglot_key(&play(&["e2e4", "d7d5"])), 0x0756b94461c50fb0);
+    assert_eq!(polyglot_key(&play(&["e2e4", "d7d5", "e4e5"])), 0x662fafb965db29d4);
+    assert_eq!(polyglot_key(&play(&["e2e4", "d7d5", "e4e5", "f7f5"])), 0x22a48b5a8e47ff78);
+    assert_eq!(polyglot_key(&play(&["e2e4", "d7d5", "e4e5", "f7f5", "e1e2"])), 0x652a607ca3f242c1);
+}
+
+#[test]
+fn book_moves() {
+    let mut bytes = entry(START, E2E4, 3);
+    bytes.extend(entry(START, D2D4, 1));
+    bytes.extend(entry(0x823c9b50fd114196, 4 | (4 << 3) | (4 << 6) | (6 << 9), 1));
+    let book = Book::from_bytes(&bytes).unwrap();
+    assert_eq!(book.len(), 3);
+
+    let moves: Vec<_> = book.moves(&ChessGame::new()).into_iter().map(|(m, w)| (uci(m), w)).collect();
+    assert_eq!(moves, vec![("e2e4".to_string(), 3), ("d2d4".to_string(), 1)]);
+
+    let reply = book.choose(&play(&["e2e4"])).unwrap();
+    assert_eq!(uci(reply), "e7e5");
+    assert!(book.choose(&play(&["d2d4"])).is_none());
+}
+
+#[test]
+fn castling_from_book() {
+    let game = play(&["e2e4", "e7e5", "g1f3", "b8c6", "f1c4", "g8f6"]);
+    let key = polyglot_key(&game);
+    // e1h1
+    let book = Book::from_bytes(&entry(key, 7 | (4 << 6), 1)).unwrap();
+    let moves = book.moves(&game);
+    assert_eq!(moves.len(), 1);
+    assert!(matches!(moves[0].0, ACastle(_)));
+}
+
+#[test]
+fn truncated_book() {
+    let bytes = entry(START, E2E4, 1);
+    assert!(matches!(Book::from_bytes(&bytes[..10]), Err(BookError::Truncated(10))));
+}
+
+#[test]
+fn booked_bot_leaves_the_book() {
+    let book = Arc::new(Book::from_bytes(&entry(START, D2D4, 1)).unwrap());
+    let mut bot = Booked::new(Box::new(Strategy::Random), book.clone(), 16);
+    assert_eq!(bot.name(), "Random");
+    assert_eq!(bot.choose(&ChessGame::new()).map(uci), Some("d2d4".to_string()));
+    // out of the book
+    assert!(bot.choose(&play(&["d2d4"])).is_some());
+
+    let mut late = Booked::new(Box::new(Strategy::Random), book, 0);
+    for _ in 0..10 {
+        if late.choose(&ChessGame::new()).map(uci) != Some("d2d4".to_string()) {
+            return;
+        }
+    }
+    panic!("always played the book move after the book ran out");
+}
+
+#[test]
+fn booked_bot_still_explains_itself() {
+    let book = Arc::new(Book::from_bytes(&entry(START, D2D4, 1)).unwrap());
+    let mut bot = Booked::new(Box::new(Strategy::Swarm), book, 16);
+    assert_eq!(bot.evaluator(), Strategy::Swarm.evaluator());
+    // the book doesn't say why
+    assert!(bot.trace(&ChessGame::new()).is_none());
+    assert!(bot.trace(&play(&["d2d4"])).is_some());
+}
@@ -0,0 +1,244 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use dumb_chess::action::{Action::{self, *}, Take};
+use dumb_chess::bot::Bot;
+use dumb_chess::final_state::{FinalState, GameResult, Reason};
+use dumb_chess::game::{ChessGame, Match, Ply, play_game, play_game_adjudicated};
+use dumb_chess::piece::Piece::*;
+use dumb_chess::player::Player::*;
+use dumb_chess::registry::Registry;
+use dumb_chess::rng;
+use dumb_chess::stateful::{Grudge, Tourist};
+use dumb_chess::strategy::{Strategy, sorted_player};
+
+#[derive(Clone, Default)]
+struct Counter {
+    games: u32,
+    moves: u32,
+}
+
+impl Bot for Counter {
+    fn name(&self) -> &str {
+        "Counter"
+    }
+
+    fn description(&self) -> &str {
+        "Counts what it has done."
+    }
+
+    fn choose(&mut self, game: &ChessGame) -> Option<Action> {
+        self.moves += 1;
+        sorted_player(game)
+    }
+
+    fn new_game(&mut self) {
+        self.games += 1;
+        self.moves = 0;
+    }
+}
+
+// counts how often it's asked for a move, from outside whatever it's in
+struct Asked(Rc<Cell<u32>>);
+
+impl Bot for Asked {
+    fn name(&self) -> &str {
+        "Asked"
+    }
+
+    fn description(&self) -> &str {
+        "Counts how often it was asked to move."
+    }
+
+    fn choose(&mut self, game: &ChessGame) -> Option<Action> {
+        self.0.set(self.0.get() + 1);
+        sorted_player(game)
+    }
+}
+
+#[test]
+fn registry_has_builtins() {
+    let registry = Registry::new();
+    assert!(registry.contains("Swarm"));
+    assert_eq!(registry.get("Give up").unwrap().description(), Strategy::GiveUp.description());
+    // the listing says the same thing as the bots themselves
+    for name in ["MCTS", "MCTS Swarm"] {
+        assert_eq!(registry.get(name).unwrap().description(), registry.create(name).unwrap().description());
+    }
+}
+
+#[test]
+fn register_custom_bot() {
+    let mut registry = Registry::new();
+    let count = registry.entries().count();
+    registry.register_bot(Counter::default());
+    assert_eq!(registry.entries().count(), count + 1);
+    assert_eq!(registry.entries().last().unwrap().name(), "Counter");
+
+    // same name replaces
+    registry.register("Counter", "Something else", || Box::new(Strategy::Random));
+    assert_eq!(registry.entries().count(), count + 1);
+    assert_eq!(registry.create("Counter").unwrap().name(), "Random");
+}
+
+#[test]
+fn play_with_custom_bot() {
+    let mut white = Counter::default();
+    let mut black = Strategy::GiveUp;
+    let (game, result) = play_game(&mut black, &mut white);
+    assert_eq!(result, GameResult::new(FinalState::Draw, Reason::NoMove));
+    assert_eq!(game.moves.len(), 1);
+    assert_eq!(white.games, 1);
+    assert_eq!(white.moves, 1);
+}
+
+#[test]
+fn grudge_takes_revenge() {
+    let mut game = ChessGame::blank();
+    let knight = Knight(1, Black);
+    let pawn = Pawn(2, White);
+    let rook = Rook(3, White);
+    let bystander = Pawn(4, Black);
+    game.board.set((7, 7).into(), King(5, White));
+    game.board.set((0, 7).into(), King(6, Black));
+    game.board.set((2, 2).into(), knight);
+    game.board.set((4, 3).into(), pawn);
+    game.board.set((7, 3).into(), rook);
+    game.board.set((7, 0).into(), bystander);
+    game.turn = Black;
+    game = game.step(ATake(Take::new(knight, pawn, (2, 2).into(), (4, 3).into())));
+
+    let mut grudge = Grudge::default();
+    let act = grudge.choose(&game).unwrap();
+    assert_eq!(grudge.grudges(), &[1]);
+    assert_eq!(act, ATake(Take::new(rook, knight, (7, 3).into(), (4, 3).into())));
+
+    grudge.new_game();
+    assert!(grudge.grudges().is_empty());
+}
+
+#[test]
+fn tourist_keeps_destination() {
+    let mut tourist = Tourist::default();
+    let game = ChessGame::new();
+    tourist.choose(&game);
+    let destination = tourist.destination().unwrap();
+    assert!(destination.row < 4);
+    tourist.choose(&game);
+    assert_eq!(tourist.destination(), Some(destination));
+    tourist.new_game();
+    assert_eq!(tourist.destination(), None);
+}
+
+#[test]
+fn exploiter_predicts_opponent() {
+    use dumb_chess::definition::parse_toml;
+    use dumb_chess::exploit::Exploiter;
+
+    // the opponent always gives up, so every move ends the game right away
+    // and the exploiter should still find a legal move
+    let defs = parse_toml(r#"
+[[strategy]]
+name = "Hustler"
+evaluator = { InCheck = "opponent" }
+chooser = "Max"
+combiner = "Max"
+exploit = { samples = 2 }
+"#).unwrap();
+    let mut registry = Registry::new();
+    registry.register_definitions(defs.clone());
+    let mut hustler = registry.create("Hustler").unwrap();
+    hustler.meet_opponent(&Strategy::Swarm);
+    let game = ChessGame::new();
+    let act = hustler.choose(&game).unwrap();
+    assert!(game.possible_moves(game.turn).any(|m| m == act));
+
+    let mut exploiter = Exploiter::new(defs[0].clone());
+    let mut giveup = Strategy::GiveUp;
+    let (game, result) = play_game(&mut giveup, &mut exploiter);
+    assert_eq!(result.state, FinalState::Draw);
+    assert_eq!(game.moves.len(), 1);
+}
+
+#[test]
+fn mcts_finds_mate() {
+    use dumb_chess::action::Move;
+    use dumb_chess::mcts::Mcts;
+
+    // back rank mate with the rook
+    let mut game = ChessGame::blank();
+    let rook = Rook(1, White);
+    game.board.set((7, 7).into(), King(0, White));
+    game.board.set((7, 0).into(), rook);
+    game.board.set((0, 6).into(), King(2, Black));
+    game.board.set((1, 5).into(), Pawn(3, Black));
+    game.board.set((1, 6).into(), Pawn(4, Black));
+    game.board.set((1, 7).into(), Pawn(5, Black));
+
+    let mut mcts = Mcts::new(500);
+    let act = mcts.choose(&game).unwrap();
+    assert_eq!(act, AMove(Move::new(rook, (7, 0).into(), (0, 0).into())));
+
+    let mut swarm = Mcts::with_rollout(20, Box::new(Strategy::Swarm));
+    assert_eq!(swarm.name(), "MCTS Swarm");
+    let game = ChessGame::new();
+    let act = swarm.choose(&game).unwrap();
+    assert!(game.possible_moves(game.turn).any(|m| m == act));
+
+    // no time to think still isn't giving up
+    let act = Mcts::new(0).choose(&game).unwrap();
+    assert!(game.possible_moves(game.turn).any(|m| m == act));
+}
+
+#[test]
+fn mcts_playouts_stop_at_fifty_moves() {
+    use dumb_chess::mcts::Mcts;
+
+    // just the kings shuffling about, so the next move is the fiftieth
+    // without a take or pawn move
+    let mut game = ChessGame::blank();
+    game.board.set((7, 7).into(), King(0, White));
+    game.board.set((0, 0).into(), King(1, Black));
+    while game.moves.len() < 49 {
+        let act = game.possible_moves(game.turn).next().unwrap();
+        game = game.step(act);
+    }
+    assert!(game.check_state().is_none());
+
+    // every move from here is a draw, so there is nothing to play out
+    let asked = Rc::new(Cell::new(0));
+    let mut mcts = Mcts::with_rollout(20, Box::new(Asked(asked.clone())));
+    assert!(mcts.choose(&game).is_some());
+    assert_eq!(asked.get(), 0);
+}
+
+#[test]
+fn judge_ends_game_early() {
+    let mut white = Strategy::Random;
+    let mut black = Strategy::Random;
+    let mut judge = |game: &ChessGame| (game.moves.len() >= 4).then_some(GameResult::new(FinalState::Win(Black), Reason::Resignation));
+    let (game, result) = play_game_adjudicated(&mut black, &mut white, &mut judge);
+    assert_eq!(game.moves.len(), 4);
+    assert_eq!(result, GameResult::new(FinalState::Win(Black), Reason::Resignation));
+}
+
+#[test]
+fn playing_a_ply_at_a_time() {
+    let (mut white, mut black) = (Strategy::Swarm, Strategy::Random);
+    let mut judge = |game: &ChessGame| (game.moves.len() >= 30).then_some(GameResult::new(FinalState::Draw, Reason::MaxPlies));
+    rng::seed(5);
+    let (whole, result) = play_game_adjudicated(&mut black, &mut white, &mut judge);
+
+    rng::seed(5);
+    let mut game = Match::new(&mut black, &mut white, &mut judge);
+    assert_eq!(game.player(White).name(), "Swarm");
+    let mut plies = 0;
+    while let Ply::Moved(_) = game.play() {
+        plies += 1;
+    }
+    assert_eq!(plies, whole.moves.len());
+    assert_eq!(game.game.moves, whole.moves);
+    assert_eq!(game.result, Some(result));
+    // and it stays over
+    assert_eq!(game.play(), Ply::Over(result));
+}
@@ -0,0 +1,59 @@
+use dumb_chess::bot::Bot;
+use dumb_chess::commentary::{comment, evaluation, evaluation_text, explain, white_share};
+use dumb_chess::definition::{Evaluator, Side::*};
+use dumb_chess::game::ChessGame;
+use dumb_chess::notation::{from_fen, parse_uci};
+use dumb_chess::strategy::Strategy;
+
+fn after(fen: &str, moves: &[&str]) -> ChessGame {
+    let mut game = from_fen(fen).unwrap();
+    for m in moves {
+        game = game.step(parse_uci(&game, m).unwrap());
+    }
+    game
+}
+
+#[test]
+fn strategies_say_why() {
+    let game = from_fen("4k3/8/8/8/8/8/8/K6R w - - 0 1").unwrap();
+    let act = parse_uci(&game, "h1e1").unwrap();
+    assert_eq!(comment(&Strategy::Swarm, &game, act).unwrap(),
+        "Swarm: reduced total distance to the enemy king from 21 to 18");
+    assert_eq!(comment(&Strategy::Ape, &game, act).unwrap(), "Ape: moved 3 squares");
+    // nothing to explain about a random move
+    assert_eq!(comment(&Strategy::Random, &game, act), None);
+    assert_eq!(Strategy::Random.evaluator(), None);
+    assert_eq!(Strategy::Swarm.evaluator(), Some(Evaluator::KingDistance(Me, Opponent)));
+}
+
+#[test]
+fn combined_evaluators() {
+    let game = from_fen("4k3/8/8/8/8/8/8/K6R w - - 0 1").unwrap();
+    let act = parse_uci(&game, "h1h8").unwrap();
+    let evaluator = Evaluator::Weighted(vec![
+        (1.0, Evaluator::InCheck(Opponent)),
+        (-1.0, Evaluator::Negate(Box::new(Evaluator::Attacks))),
+    ]);
+    assert_eq!(explain(&evaluator, &game, act), "gave check, didn't take anything");
+}
+
+#[test]
+fn definitions_say_why_too() {
+    let defs = dumb_chess::definition::parse(
+        "[[strategy]]\nname = \"Hungry\"\nevaluator = { Material = \"self\" }\nchooser = \"Max\"\ncombiner = \"Max\"\n").unwrap();
+    let game = from_fen("4k3/8/8/8/8/8/8/K3r2R w - - 0 1").unwrap();
+    let act = parse_uci(&game, "h1e1").unwrap();
+    let bot: &dyn Bot = &defs[0];
+    assert_eq!(comment(bot, &game, act).unwrap(), "Hungry: increased material lead from 0 to 5");
+}
+
+#[test]
+fn evaluating_positions() {
+    assert_eq!(evaluation(&ChessGame::new()), 0.0);
+    let game = after("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1", &["e2e4", "d7d5", "e4d5"]);
+    assert_eq!(evaluation(&game), 1.0);
+    assert_eq!(evaluation_text(1.0), "+1");
+    assert_eq!(evaluation_text(-3.0), "-3");
+    assert_eq!(white_share(0.0), 0.5);
+    assert!(white_share(9.0) > 0.8 && white_share(-9.0) < 0.2);
+}
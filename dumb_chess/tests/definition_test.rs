@@ -0,0 +1,158 @@
+use dumb_chess::definition::*;
+use dumb_chess::game::ChessGame;
+use dumb_chess::registry::Registry;
+use dumb_chess::strategy::Strategy;
+
+const STALKER: &str = r#"
+[[strategy]]
+name = "Stalker"
+description = "Chase the enemy king."
+evaluator = { KingDistance = ["self", "opponent"] }
+chooser = "Min"
+combiner = "Min"
+"#;
+
+#[test]
+fn parse_toml_definition() {
+    let defs = parse_toml(STALKER).unwrap();
+    assert_eq!(defs.len(), 1);
+    assert_eq!(defs[0].name, "Stalker");
+    assert_eq!(defs[0].evaluator, Evaluator::KingDistance(Side::Me, Side::Opponent));
+    assert_eq!(defs[0].chooser, Chooser::Min);
+    assert_eq!(defs[0].combiner, Combiner::Min);
+    assert_eq!(defs[0].depth, 1);
+}
+
+#[test]
+fn parse_json_definition() {
+    let text = r#"{"strategy": [{"name": "Counter", "evaluator": {"MoveAmount": "opponent"}, "chooser": "Max", "combiner": {"Constant": 2.0}, "depth": 2}]}"#;
+    let defs = parse(text).unwrap();
+    assert_eq!(defs[0].evaluator, Evaluator::MoveAmount(Side::Opponent));
+    assert_eq!(defs[0].combiner, Combiner::Constant(2.0));
+    assert_eq!(defs[0].depth, 2);
+}
+
+#[test]
+fn zero_depth_rejected() {
+    let text = format!("{}depth = 0\n", STALKER);
+    assert!(matches!(parse_toml(&text), Err(DefinitionError::Invalid(_))));
+}
+
+#[test]
+fn bad_chooser_numbers_rejected() {
+    for chooser in [
+        "{ Softmax = 0.0 }",
+        "{ Softmin = -1.0 }",
+        "{ EpsilonGreedy = { epsilon = 1.5, chooser = \"Min\" } }",
+        "{ EpsilonGreedy = { epsilon = -0.1, chooser = \"Min\" } }",
+        "{ EpsilonGreedy = { epsilon = 0.5, chooser = { Softmax = 0.0 } } }",
+    ] {
+        let text = STALKER.replace("chooser = \"Min\"", &format!("chooser = {}", chooser));
+        assert!(matches!(parse_toml(&text), Err(DefinitionError::Invalid(_))), "{}", chooser);
+    }
+    // tie breaks get checked too
+    let text = format!("{}[[strategy.tie_break]]\nevaluator = \"BigPlays\"\nchooser = {{ Softmin = 0.0 }}\ncombiner = \"Max\"\n", STALKER);
+    assert!(matches!(parse_toml(&text), Err(DefinitionError::Invalid(_))));
+}
+
+#[test]
+fn definitions_in_registry() {
+    let defs = parse_toml(STALKER).unwrap();
+    let mut registry = Registry::new();
+    registry.register_definitions(defs);
+    assert!(registry.contains("Random"));
+    // listed along with the built in strategies
+    assert!(registry.entries().any(|e| e.name() == "Stalker"));
+    let mut stalker = registry.create("Stalker").unwrap();
+    assert_eq!(stalker.description(), "Chase the enemy king.");
+
+    let game = ChessGame::new();
+    let act = stalker.choose(&game).unwrap();
+    assert!(game.possible_moves(game.turn).any(|m| m == act));
+}
+
+#[test]
+fn combined_evaluators() {
+    use dumb_chess::action::{Action::AMove, Move};
+    use dumb_chess::piece::Piece::Pawn;
+    use dumb_chess::player::Player::White;
+
+    let text = r#"
+[[strategy]]
+name = "Mixed"
+evaluator = { Weighted = [[2.0, "BigPlays"], [1.0, { Negate = "BigPlays" }], [10.0, { Threshold = { evaluator = "BigPlays", at = 2.0 } }]] }
+chooser = "Max"
+combiner = "Max"
+"#;
+    let defs = parse_toml(text).unwrap();
+    let eval = defs[0].evaluator.build(White);
+    let game = ChessGame::new();
+    let short = AMove(Move::new(Pawn(16, White), (6, 0).into(), (5, 0).into()));
+    let long = AMove(Move::new(Pawn(16, White), (6, 0).into(), (4, 0).into()));
+    assert_eq!(eval.eval(short, &game.step(short)), 1.0);
+    assert_eq!(eval.eval(long, &game.step(long)), 12.0);
+}
+
+#[test]
+fn tie_break_stages() {
+    // everything ties on not being in check, so the biggest move (a knight) decides
+    let text = r#"
+[[strategy]]
+name = "Safe queen"
+evaluator = { InCheck = "self" }
+chooser = "Min"
+combiner = "Min"
+[[strategy.tie_break]]
+evaluator = "BigPlays"
+chooser = "Max"
+combiner = "Max"
+"#;
+    let defs = parse_toml(text).unwrap();
+    assert_eq!(defs[0].tie_break.len(), 1);
+    let game = ChessGame::new();
+    let act = defs[0].run(&game).unwrap();
+    assert_eq!(act_distance(act), 3);
+}
+
+fn act_distance(act: dumb_chess::action::Action) -> i32 {
+    match act {
+        dumb_chess::action::Action::AMove(m) => m.from.man_dist(m.to),
+        _ => panic!("only plain moves are possible at the start"),
+    }
+}
+
+#[test]
+fn random_choosers() {
+    let text = r#"
+[[strategy]]
+name = "Warm"
+evaluator = "BigPlays"
+chooser = { Softmax = 0.0001 }
+combiner = "Max"
+
+[[strategy]]
+name = "Jittery"
+evaluator = "BigPlays"
+chooser = { EpsilonGreedy = { epsilon = 0.0, chooser = { Softmin = 0.0001 } } }
+combiner = "Max"
+"#;
+    let defs = parse_toml(text).unwrap();
+    let game = ChessGame::new();
+    // a tiny temperature is the same as taking the max
+    assert_eq!(act_distance(defs[0].run(&game).unwrap()), 3);
+    // no exploring and a tiny temperature is the same as taking the min
+    assert_eq!(act_distance(defs[1].run(&game).unwrap()), 1);
+}
+
+#[test]
+fn seeded_games_repeat() {
+    use dumb_chess::game::play_game;
+    use dumb_chess::rng;
+
+    let play = || {
+        rng::seed(7);
+        let (game, state) = play_game(&mut Strategy::Random, &mut Strategy::Sleepy);
+        (game.moves, state)
+    };
+    assert_eq!(play(), play());
+}
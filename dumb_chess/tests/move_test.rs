@@ -1,8 +1,8 @@
-use chess::game::ChessGame;
-use chess::piece::Piece::*;
-use chess::player::Player::*;
-use chess::action::Action::*;
-use chess::action::*;
+use dumb_chess::game::ChessGame;
+use dumb_chess::piece::Piece::*;
+use dumb_chess::player::Player::*;
+use dumb_chess::action::Action::*;
+use dumb_chess::action::*;
 
 #[test]
 fn king_moves() {
@@ -10,7 +10,7 @@ fn king_moves() {
     let king = King(0, Black);
     let orig = (4,4).into();
     game.board.set(orig, king);
-    let mut moves = game.available_moves(Black);
+    let mut moves = game.available_moves(Black).collect::<Vec<_>>();
     let mut expected = vec![
         AMove(Move::new(king, orig, orig + (0,1).into())),
         AMove(Move::new(king, orig, orig + (0,-1).into())),
@@ -33,7 +33,7 @@ fn knight_moves() {
     let knight = Knight(0, Black);
     let orig = (4,4).into();
     game.board.set(orig, knight);
-    let mut moves = game.available_moves(Black);
+    let mut moves = game.available_moves(Black).collect::<Vec<_>>();
     let mut expected = vec![
         AMove(Move::new(knight, orig, orig + (1,2).into())),
         AMove(Move::new(knight, orig, orig + (2,1).into())),
@@ -56,7 +56,7 @@ fn bishop_moves() {
     let bishop = Bishop(0, Black);
     let orig = (4,4).into();
     game.board.set(orig, bishop);
-    let mut moves = game.available_moves(Black);
+    let mut moves = game.available_moves(Black).collect::<Vec<_>>();
     let mut expected = vec![
         AMove(Move::new(bishop, orig, orig + (1,1).into())),
         AMove(Move::new(bishop, orig, orig + (2,2).into())),
@@ -84,7 +84,7 @@ fn rook_moves() {
     let rook = Rook(0, Black);
     let orig = (4,4).into();
     game.board.set(orig, rook);
-    let mut moves = game.available_moves(Black);
+    let mut moves = game.available_moves(Black).collect::<Vec<_>>();
     let mut expected = vec![
         AMove(Move::new(rook, orig, orig + (1,0).into())),
         AMove(Move::new(rook, orig, orig + (2,0).into())),
@@ -113,7 +113,7 @@ fn queen_moves() {
     let queen = Queen(0, Black);
     let orig = (4,4).into();
     game.board.set(orig, queen);
-    let mut moves = game.available_moves(Black);
+    let mut moves = game.available_moves(Black).collect::<Vec<_>>();
     let mut expected = vec![
         AMove(Move::new(queen, orig, orig + (1,0).into())),
         AMove(Move::new(queen, orig, orig + (2,0).into())),
@@ -155,7 +155,7 @@ fn pawn_moves() {
     let pawn = Pawn(0, Black);
     let orig = (4,4).into();
     game.board.set(orig, pawn);
-    let mut moves = game.available_moves(Black);
+    let mut moves = game.available_moves(Black).collect::<Vec<_>>();
     // hasn't moved before
     let mut expected = vec![
         AMove(Move::new(pawn, orig, orig + Black.pawn_dir())),
@@ -176,7 +176,7 @@ fn castling() {
     let king_orig = (0,3).into();
     game.board.set(rook_orig, rook);
     game.board.set(king_orig, king);
-    let moves = game.available_moves(Black);
+    let moves = game.available_moves(Black).collect::<Vec<_>>();
     let castle = ACastle(Castle::new(king, rook, king_orig, king_orig + (0, -2).into(), rook_orig, king_orig + (0, -1).into()));
     assert!(moves.contains(&castle));
 }
@@ -192,7 +192,7 @@ fn no_castling() {
     game.board.set((7, 2).into(), op_rook);
     game.board.set(rook_orig, rook);
     game.board.set(king_orig, king);
-    let moves = game.available_moves(Black);
+    let moves = game.available_moves(Black).collect::<Vec<_>>();
     let castle = ACastle(Castle::new(king, rook, king_orig, king_orig + (0, -2).into(), rook_orig, king_orig + (0, -1).into()));
     assert!(!moves.contains(&castle));
 }
@@ -206,7 +206,7 @@ fn en_passant() {
     game.board.set(orig, pawn1);
     game = game.step(AMove(Move::new(pawn1, orig, orig + (2, 0).into())));
     game.board.set((3,4).into(), pawn2);
-    let moves = game.available_moves(White);
+    let moves = game.available_moves(White).collect::<Vec<_>>();
     let passant = APassant(Passant::new(pawn2, pawn1, (3,4).into(), (2,3).into(), (3,3).into()));
 
     assert!(moves.contains(&passant));
@@ -221,7 +221,7 @@ fn knight_jump() {
     game.board.set((0,0).into(), knight);
     game.board.set((1,0).into(), pawn1);
     game.board.set((0,1).into(), pawn2);
-    let moves = game.available_moves(Black);
+    let moves = game.available_moves(Black).collect::<Vec<_>>();
 
     let move1 = AMove(Move::new(knight, (0,0).into(), (1,2).into()));
     let move2 = AMove(Move::new(knight, (0,0).into(), (2,1).into()));
@@ -237,7 +237,7 @@ fn rook_take() {
     let rook = Rook(1, Black);
     game.board.set((1,2).into(), pawn);
     game.board.set((4,2).into(), rook);
-    let moves = game.available_moves(Black);
+    let moves = game.available_moves(Black).collect::<Vec<_>>();
 
     let take = ATake(Take::new(rook, pawn, (4,2).into(), (1,2).into()));
 
@@ -254,7 +254,7 @@ fn rook_take1() {
     game.board.set((1,2).into(), pawn1);
     game.board.set((0,2).into(), pawn2);
     game.board.set((4,2).into(), rook);
-    let moves = game.available_moves(Black);
+    let moves = game.available_moves(Black).collect::<Vec<_>>();
 
     let take1 = ATake(Take::new(rook, pawn1, (4,2).into(), (1,2).into()));
     let take2 = ATake(Take::new(rook, pawn1, (4,2).into(), (0,2).into()));
@@ -273,7 +273,7 @@ fn rook_blocked() {
     game.board.set((1,2).into(), pawn1);
     game.board.set((0,2).into(), pawn2);
     game.board.set((4,2).into(), rook);
-    let moves = game.available_moves(Black);
+    let moves = game.available_moves(Black).collect::<Vec<_>>();
 
     assert_eq!(moves.into_iter().filter(|x| matches!(x, ATake(_))).count(), 0);
 }
@@ -285,7 +285,7 @@ fn queen_take() {
     let queen = Queen(1, Black);
     game.board.set((1,2).into(), pawn);
     game.board.set((4,2).into(), queen);
-    let moves = game.available_moves(Black);
+    let moves = game.available_moves(Black).collect::<Vec<_>>();
 
     let take = ATake(Take::new(queen, pawn, (4,2).into(), (1,2).into()));
 
@@ -302,7 +302,7 @@ fn queen_take1() {
     game.board.set((1,2).into(), pawn1);
     game.board.set((0,2).into(), pawn2);
     game.board.set((4,2).into(), queen);
-    let moves = game.available_moves(Black);
+    let moves = game.available_moves(Black).collect::<Vec<_>>();
 
     let take1 = ATake(Take::new(queen, pawn1, (4,2).into(), (1,2).into()));
     let take2 = ATake(Take::new(queen, pawn1, (4,2).into(), (0,2).into()));
@@ -321,7 +321,7 @@ fn queen_blocked() {
     game.board.set((1,2).into(), pawn1);
     game.board.set((0,2).into(), pawn2);
     game.board.set((4,2).into(), queen);
-    let moves = game.available_moves(Black);
+    let moves = game.available_moves(Black).collect::<Vec<_>>();
 
     assert_eq!(moves.into_iter().filter(|x| matches!(x, ATake(_))).count(), 0);
 }
@@ -333,7 +333,7 @@ fn bishop_take() {
     let bishop = Bishop(1, Black);
     game.board.set((1,1).into(), pawn);
     game.board.set((4,4).into(), bishop);
-    let moves = game.available_moves(Black);
+    let moves = game.available_moves(Black).collect::<Vec<_>>();
 
     let take = ATake(Take::new(bishop, pawn, (4,4).into(), (1,1).into()));
 
@@ -350,7 +350,7 @@ fn bishop_take1() {
     game.board.set((1,1).into(), pawn1);
     game.board.set((0,0).into(), pawn2);
     game.board.set((4,4).into(), bishop);
-    let moves = game.available_moves(Black);
+    let moves = game.available_moves(Black).collect::<Vec<_>>();
 
     let take1 = ATake(Take::new(bishop, pawn1, (4,4).into(), (1,1).into()));
     let take2 = ATake(Take::new(bishop, pawn1, (4,4).into(), (0,0).into()));
@@ -369,7 +369,7 @@ fn bishop_blocked() {
     game.board.set((1,1).into(), pawn1);
     game.board.set((0,0).into(), pawn2);
     game.board.set((4,4).into(), bishop);
-    let moves = game.available_moves(Black);
+    let moves = game.available_moves(Black).collect::<Vec<_>>();
 
     assert_eq!(moves.into_iter().filter(|x| matches!(x, ATake(_))).count(), 0);
 }
@@ -381,7 +381,7 @@ fn pawn_take() {
     let rook = Rook(2, Black);
     game.board.set((4,5).into(), pawn);
     game.board.set((3,4).into(), rook);
-    let moves = game.available_moves(White);
+    let moves = game.available_moves(White).collect::<Vec<_>>();
 
     let take = ATake(Take::new(pawn, rook, (4,5).into(), (3,4).into()));
 
@@ -394,7 +394,7 @@ fn all_promote() {
     let pawn = Pawn(0, White);
     game.board.set((2,0).into(), pawn);
     game = game.step(AMove(Move::new(pawn, (2,0).into(), (1,0).into())));
-    let moves = game.available_moves(White);
+    let moves = game.available_moves(White).collect::<Vec<_>>();
 
     let promote_queen = APromote(Promote::new(pawn, Queen(0, White), (1,0).into(), (0,0).into()));
     let promote_rook = APromote(Promote::new(pawn, Rook(0, White), (1,0).into(), (0,0).into()));
@@ -415,7 +415,7 @@ fn all_promote_take() {
     game.board.set((2,0).into(), pawn);
     game = game.step(AMove(Move::new(pawn, (2,0).into(), (1,0).into())));
     game.board.set((0,1).into(), rook);
-    let moves = game.available_moves(White);
+    let moves = game.available_moves(White).collect::<Vec<_>>();
 
     let promote_queen = APromoteTake(PromoteTake::new(pawn, Queen(0, White), rook, (1,0).into(), (0,1).into()));
     let promote_rook = APromoteTake(PromoteTake::new(pawn, Rook(0, White), rook, (1,0).into(), (0,1).into()));
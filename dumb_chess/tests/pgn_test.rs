@@ -0,0 +1,46 @@
+use dumb_chess::game::{ChessGame, play_game};
+use dumb_chess::notation::{from_fen, from_pgn, movetext_from, parse_san, parse_uci, to_fen, write_pgn, ParsePgnError};
+use dumb_chess::rng;
+use dumb_chess::strategy::Strategy;
+
+#[test]
+fn reading_back_written_games() {
+    for seed in 0..3 {
+        rng::seed(seed);
+        let (end, _) = play_game(&mut Strategy::Random, &mut Strategy::Random);
+        let tags = [("White", "Random".to_string()), ("Black", "Random \"the second\"".to_string())];
+        let pgn = write_pgn(&tags, &ChessGame::new(), &end.moves, "*");
+        let read = from_pgn(&pgn).unwrap();
+        assert_eq!(read.moves, end.moves);
+        assert_eq!(read.tag("Black"), Some("Random \"the second\""));
+        assert_eq!(read.tag("FEN"), None);
+    }
+}
+
+#[test]
+fn comments_variations_and_numbers() {
+    let pgn = "[Event \"?\"]\n\n1.e4 {best by test} e5 (1... c5 2. Nf3 (2. c3)) 2. Nf3 $1 Nc6; the usual\n3. Bb5!? a6 4. O-O 1-0\n\n[Event \"next\"]\n\n1. d4 *";
+    let read = from_pgn(pgn).unwrap();
+    let uci: Vec<String> = read.moves.iter().map(|m| dumb_chess::notation::uci(*m)).collect();
+    assert_eq!(uci, ["e2e4", "e7e5", "g1f3", "b8c6", "f1b5", "a7a6", "e1g1"]);
+
+    assert!(matches!(from_pgn("1. e4 e4"), Err(ParsePgnError::Move(2, m)) if m == "e4"));
+    assert!(matches!(from_pgn("[FEN \"not a fen\"]\n\n1. e4"), Err(ParsePgnError::Fen(_))));
+}
+
+#[test]
+fn starting_from_a_position() {
+    let fen = "r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 10";
+    let start = from_fen(fen).unwrap();
+    let castle = parse_san(&start, "0-0-0").unwrap();
+    let after = start.step(castle);
+    let reply = parse_san(&after, "Kf1").unwrap();
+    assert_eq!(movetext_from(&start, &[castle, reply]), "10... O-O-O 11. Kf1");
+
+    let pgn = write_pgn(&[("Event", "?".to_string())], &start, &[castle, reply], "*");
+    assert!(pgn.contains("[SetUp \"1\"]\n[FEN \"r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 10\"]\n"));
+    let read = from_pgn(&pgn).unwrap();
+    assert_eq!(to_fen(&read.start), fen);
+    assert_eq!(read.moves, [castle, reply]);
+    assert_eq!(parse_uci(&start, "e8c8"), Some(castle));
+}
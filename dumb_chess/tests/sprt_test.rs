@@ -0,0 +1,62 @@
+use dumb_chess::adjudication::{Adjudicate, Adjudicator, Rules};
+use dumb_chess::batch::Batch;
+use dumb_chess::registry::Registry;
+use dumb_chess::sprt::{self, Pentanomial, Sprt, Status, elo_to_score, score_to_elo};
+
+fn judge() -> Box<dyn Adjudicate> {
+    Box::new(Adjudicator::new(Rules { max_plies: Some(200), ..Rules::default() }))
+}
+
+#[test]
+fn pentanomial_counts() {
+    let mut results = Pentanomial::default();
+    results.add_pair(1.0, 1.0);
+    results.add_pair(1.0, 0.5);
+    results.add_pair(0.5, 0.5);
+    results.add_pair(0.0, 1.0);
+    results.add_pair(0.0, 0.0);
+    assert_eq!(results.counts, [1, 0, 2, 1, 1]);
+    assert_eq!(results.pairs(), 5);
+    assert!((results.score().unwrap() - 0.55).abs() < 1e-9);
+    assert!((score_to_elo(elo_to_score(35.0)) - 35.0).abs() < 1e-9);
+    assert_eq!(elo_to_score(0.0), 0.5);
+}
+
+#[test]
+fn llr_direction() {
+    let test = Sprt::default();
+    let (lower, upper) = test.bounds();
+    assert!((upper - 2.944).abs() < 0.001);
+    assert!((lower + 2.944).abs() < 0.001);
+    assert_eq!(test.llr(&Pentanomial::default()), 0.0);
+    // every pair exactly even says nothing either way
+    assert_eq!(test.llr(&Pentanomial { counts: [0, 0, 50, 0, 0] }), 0.0);
+
+    let winning = Pentanomial { counts: [5, 10, 40, 25, 20] };
+    let losing = Pentanomial { counts: [20, 25, 40, 10, 5] };
+    assert!(test.llr(&winning) > 0.0);
+    assert!(test.llr(&losing) < 0.0);
+    assert_eq!(test.status(&Pentanomial { counts: [50, 100, 400, 250, 200] }), Status::AcceptH1);
+    assert_eq!(test.status(&Pentanomial { counts: [200, 250, 400, 100, 50] }), Status::AcceptH0);
+}
+
+#[test]
+fn pairs_swap_colors() {
+    let games = sprt::pairs("Swarm", "Random", 3, 2, 1);
+    assert_eq!(games.len(), 4);
+    assert_eq!((games[0].white.as_str(), games[0].black.as_str()), ("Swarm", "Random"));
+    assert_eq!((games[1].white.as_str(), games[1].black.as_str()), ("Random", "Swarm"));
+    assert_eq!(games[0].seed, games[1].seed);
+    assert_ne!(games[0].seed, games[2].seed);
+}
+
+#[test]
+fn swarm_beats_random() {
+    let registry = Registry::new();
+    let batch = Batch::new(|name| registry.create(name), judge);
+    let mut chunks = 0;
+    let result = sprt::run(&batch, &Sprt::default(), "Swarm", "Random", 500, 3, |_, _| chunks += 1).unwrap();
+    assert_eq!(result.status, Status::AcceptH1);
+    assert!(result.results.pairs() < 500);
+    assert!(chunks > 0);
+}
@@ -0,0 +1,88 @@
+#![cfg(feature = "syzygy")]
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use dumb_chess::bot::Bot;
+use dumb_chess::final_state::FinalState::*;
+use dumb_chess::game::ChessGame;
+use dumb_chess::piece::Piece::*;
+use dumb_chess::player::Player::*;
+use dumb_chess::strategy::Strategy;
+use dumb_chess::tablebase::{Endgame, Tablebase, TablebaseError, Tablebased};
+
+// the tables are too big to keep in the repo, point SYZYGY_PATH at a directory
+// with at least KQvK in it and run with --ignored for the probing tests
+fn tables() -> Tablebase {
+    let path = PathBuf::from(std::env::var_os("SYZYGY_PATH").expect("SYZYGY_PATH should be set"));
+    Tablebase::open(&path).unwrap()
+}
+
+fn king_and_queen() -> ChessGame {
+    let mut game = ChessGame::blank();
+    game.board.set((7, 4).into(), King(0, White));
+    game.board.set((7, 3).into(), Queen(1, White));
+    game.board.set((0, 4).into(), King(2, Black));
+    game
+}
+
+#[test]
+fn missing_tables() {
+    let empty = std::env::temp_dir().join("dumb_chess_no_tables");
+    std::fs::create_dir_all(&empty).unwrap();
+    assert!(matches!(Tablebase::open(&empty), Err(TablebaseError::NoTables(_))));
+    assert!(matches!(Tablebase::open(&empty.join("nowhere")), Err(TablebaseError::Io(_))));
+}
+
+#[test]
+fn counts_only_pieces() {
+    // the tables are picked by how many pieces are left, not squares
+    assert_eq!(king_and_queen().board.piece_count(), 3);
+    assert_eq!(ChessGame::new().board.piece_count(), 32);
+    assert_eq!(ChessGame::blank().board.piece_count(), 0);
+}
+
+#[test]
+fn no_tables_know_nothing() {
+    let tables = Arc::new(Tablebase::new());
+    let game = king_and_queen();
+    assert_eq!(tables.result(&game), None);
+    assert_eq!(tables.best_move(&game), None);
+
+    // so the bot plays every move itself, and still says why
+    let mut bot = Tablebased::new(Box::new(Strategy::Swarm), tables, Endgame::Best);
+    assert_eq!(bot.name(), "Swarm");
+    assert_eq!(bot.evaluator(), Strategy::Swarm.evaluator());
+    assert!(bot.trace(&game).is_some());
+    let act = bot.choose(&game).unwrap();
+    assert!(game.possible_moves(White).any(|m| m == act));
+}
+
+#[test]
+#[ignore = "needs SYZYGY_PATH"]
+fn probing() {
+    let tables = tables();
+    let game = king_and_queen();
+    assert_eq!(tables.result(&game), Some(Win(White)));
+    // too many pieces to know
+    assert_eq!(tables.result(&ChessGame::new()), None);
+    assert_eq!(tables.best_move(&ChessGame::new()), None);
+
+    let best = game.step(tables.best_move(&game).unwrap());
+    assert_eq!(tables.result(&best), Some(Win(White)));
+    // the only way to not win is to give the queen away
+    let worst = game.step(tables.worst_move(&game).unwrap());
+    assert_eq!(tables.result(&worst), Some(Draw));
+}
+
+#[test]
+#[ignore = "needs SYZYGY_PATH"]
+fn tablebased_bot() {
+    let tables = Arc::new(tables());
+    let game = king_and_queen();
+    let mut bot = Tablebased::new(Box::new(Strategy::Random), tables.clone(), Endgame::Best);
+    assert_eq!(bot.name(), "Random");
+    assert_eq!(bot.choose(&game), tables.best_move(&game));
+    // falls back to the bot outside of the tables
+    assert!(bot.choose(&ChessGame::new()).is_some());
+}
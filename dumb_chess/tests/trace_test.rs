@@ -0,0 +1,76 @@
+use dumb_chess::definition::parse_toml;
+use dumb_chess::game::ChessGame;
+use dumb_chess::notation::{from_fen, parse_uci};
+use dumb_chess::rng;
+use dumb_chess::strategy::Strategy;
+
+fn middlegame() -> ChessGame {
+    from_fen("r1bqk2r/pppp1ppp/2n2n2/2b1p3/2B1P3/3P1N2/PPP2PPP/RNBQK2R w KQkq - 1 5").unwrap()
+}
+
+#[test]
+fn trace_picks_the_same_move() {
+    for game in [ChessGame::new(), middlegame()] {
+        for s in enum_iterator::all::<Strategy>() {
+            rng::seed(11);
+            let played = s.run(&game);
+            rng::seed(11);
+            match s.trace(&game) {
+                Some(trace) => assert_eq!(trace.chosen, played, "{}", s.name()),
+                None => assert!(matches!(s, Strategy::Random | Strategy::GiveUp)),
+            }
+        }
+    }
+}
+
+#[test]
+fn trace_has_the_whole_tree() {
+    let game = middlegame();
+    rng::seed(3);
+    let trace = Strategy::Offensive.trace(&game).unwrap();
+    assert_eq!(trace.stages.len(), 1);
+    let stage = &trace.stages[0];
+    assert_eq!(stage.depth, 2);
+    assert_eq!(stage.candidates.len(), game.possible_moves(game.turn).count());
+    // Bxf7+ gives check right away, so one of its replies is worth 1
+    let bxf7 = parse_uci(&game, "c4f7").unwrap();
+    let node = stage.candidates.iter().find(|c| c.action == bxf7).unwrap();
+    assert_eq!(node.san, "Bxf7+");
+    assert!(!node.children.is_empty());
+    assert_eq!(node.value, node.children.iter().map(|c| c.value).fold(f32::MIN, f32::max));
+    assert!(stage.tied.contains(&trace.chosen.unwrap()));
+    assert!(stage.tied.iter().all(|a| stage.candidates.iter().any(|c| c.action == *a && Some(c.value) == stage.value)));
+
+    let json: serde_json::Value = serde_json::from_str(&trace.to_json()).unwrap();
+    assert_eq!(json["stages"][0]["candidates"].as_array().unwrap().len(), stage.candidates.len());
+    let dot = trace.to_dot();
+    assert!(dot.starts_with("digraph trace {"));
+    assert!(dot.contains("fillcolor"));
+}
+
+#[test]
+fn trace_tie_break_stages() {
+    let text = r#"
+[[strategy]]
+name = "Safe queen"
+evaluator = { InCheck = "self" }
+chooser = "Min"
+combiner = "Min"
+[[strategy.tie_break]]
+evaluator = "BigPlays"
+chooser = "Max"
+combiner = "Max"
+"#;
+    let def = &parse_toml(text).unwrap()[0];
+    let game = ChessGame::new();
+    rng::seed(5);
+    let played = def.run(&game);
+    rng::seed(5);
+    let trace = def.trace(&game);
+    assert_eq!(trace.chosen, played);
+    assert_eq!(trace.stages.len(), 2);
+    // nothing is in check, so every move goes on to the tie break
+    assert_eq!(trace.stages[0].tied.len(), 20);
+    assert_eq!(trace.stages[1].candidates.len(), 20);
+    assert!(trace.to_string().contains("Plays N"));
+}
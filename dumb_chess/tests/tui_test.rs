@@ -0,0 +1,64 @@
+#![cfg(feature = "tui")]
+
+use dumb_chess::final_state::Reason;
+use dumb_chess::game::Match;
+use dumb_chess::strategy::Strategy;
+use dumb_chess::tui::Viewer;
+use ratatui::{backend::TestBackend, crossterm::event::KeyCode, Terminal};
+
+fn screen(viewer: &Viewer) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|frame| viewer.draw(frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    buffer.content.chunks(buffer.area.width as usize)
+        .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>() + "\n")
+        .collect()
+}
+
+#[test]
+fn showing_the_game() {
+    let (mut white, mut black, mut judge) = (Strategy::Swarm, Strategy::Ape, ());
+    let mut viewer = Viewer::new(Match::new(&mut black, &mut white, &mut judge));
+    let start = screen(&viewer);
+    assert!(start.contains("White: Swarm"));
+    assert!(start.contains("Big moves are the only way"));
+    assert!(start.contains("White to move"));
+    assert!(start.contains("♜"));
+
+    viewer.advance();
+    viewer.advance();
+    assert_eq!(viewer.sans.len(), 2);
+    let shown = screen(&viewer);
+    assert!(shown.contains(&format!("1. {:<8} {}", viewer.sans[0], viewer.sans[1])));
+}
+
+#[test]
+fn controls() {
+    let (mut white, mut black, mut judge) = (Strategy::Random, Strategy::Random, ());
+    let mut viewer = Viewer::new(Match::new(&mut black, &mut white, &mut judge));
+    let delay = viewer.delay();
+    viewer.key(KeyCode::Char('+'));
+    assert!(viewer.delay() < delay);
+    viewer.key(KeyCode::Char('-'));
+    viewer.key(KeyCode::Char('-'));
+    assert!(viewer.delay() > delay);
+
+    viewer.key(KeyCode::Char(' '));
+    assert!(viewer.paused);
+    assert!(screen(&viewer).contains("paused"));
+    // a step plays one move and stays paused
+    viewer.key(KeyCode::Char('s'));
+    assert_eq!(viewer.sans.len(), 1);
+    assert!(viewer.paused);
+    viewer.key(KeyCode::Char('q'));
+    assert!(viewer.quit);
+}
+
+#[test]
+fn showing_the_result() {
+    let (mut white, mut black, mut judge) = (Strategy::GiveUp, Strategy::Random, ());
+    let mut viewer = Viewer::new(Match::new(&mut black, &mut white, &mut judge));
+    viewer.advance();
+    assert_eq!(viewer.over().map(|r| r.reason), Some(Reason::NoMove));
+    assert!(screen(&viewer).contains("Final state: Draw (NoMove)"));
+}